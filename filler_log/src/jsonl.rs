@@ -0,0 +1,147 @@
+use serde::{Deserialize, Serialize};
+
+use crate::record::*;
+
+// One JSON object per line, tagged with its `type`:
+// {"type":"game","players":["solution/my_robot/target/release/my_robot","linux_robots/terminator"]}
+// {"type":"turn","board":["....","..@."],"piece":[".OO"],"answer":{"player":1,"x":7,"y":1}}
+// {"type":"turn","changes":[[8,2,"a"],[9,2,"a"]],"piece":["OO"],"answer":{"player":2,"x":7,"y":11}}
+// {"type":"error","turn":0,"message":"Error: Timeout for player1"}
+// {"type":"summary","seed":1758654202459825665,"result":{"scores":[215,52],"winner":1}}
+// Only the first turn holds the whole board, the others hold the cells that changed since
+// the previous turn as [x, y, symbol].
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Line {
+    Game {
+        players: [String; 2],
+    },
+    Turn {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        board: Option<Vec<String>>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        changes: Vec<(usize, usize, char)>,
+        piece: Vec<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        answer: Option<Answer>,
+    },
+    Error(EngineError),
+    Summary {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seed: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        result: Option<GameResult>,
+    },
+}
+
+impl GameRecord {
+    pub fn to_jsonl(&self) -> String {
+        let mut lines = vec![Line::Game { players: self.players.clone() }];
+
+        let mut previous: Option<&Grid> = None;
+        for turn in &self.turns {
+            let (board, changes) = match previous {
+                Some(before) if before.width == turn.board.width && before.height == turn.board.height => {
+                    (None, changed_cells(before, &turn.board))
+                }
+                _ => (Some(turn.board.rows.clone()), Vec::new()),
+            };
+            lines.push(Line::Turn {
+                board,
+                changes,
+                piece: turn.piece.rows.clone(),
+                answer: turn.answer,
+            });
+            previous = Some(&turn.board);
+        }
+
+        lines.extend(self.errors.iter().cloned().map(Line::Error));
+        if self.seed.is_some() || self.result.is_some() {
+            lines.push(Line::Summary { seed: self.seed, result: self.result.clone() });
+        }
+
+        lines.iter()
+            .map(|line| serde_json::to_string(line).expect("a record always serializes") + "\n")
+            .collect()
+    }
+
+    pub fn from_jsonl(text: &str) -> Result<Self, String> {
+        let mut record = GameRecord::default();
+
+        for (i, line) in text.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let line: Line = serde_json::from_str(line).map_err(|err| format!("line {}: {}", i + 1, err))?;
+            match line {
+                Line::Game { players } => record.players = players,
+                Line::Turn { board, changes, piece, answer } => {
+                    let board = match (board, record.turns.last()) {
+                        (Some(rows), _) => Grid::new(rows),
+                        (None, Some(previous)) => apply_changes(&previous.board, &changes)
+                            .map_err(|err| format!("line {}: {}", i + 1, err))?,
+                        (None, None) => return Err(format!("line {}: first turn without a board", i + 1)),
+                    };
+                    record.turns.push(Turn { board, piece: Grid::new(piece), answer });
+                }
+                Line::Error(error) => record.errors.push(error),
+                Line::Summary { seed, result } => {
+                    record.seed = seed;
+                    record.result = result;
+                }
+            }
+        }
+        Ok(record)
+    }
+}
+
+fn changed_cells(before: &Grid, after: &Grid) -> Vec<(usize, usize, char)> {
+    let mut changes = Vec::new();
+    for (y, (old, new)) in before.rows.iter().zip(&after.rows).enumerate() {
+        for (x, (a, b)) in old.chars().zip(new.chars()).enumerate() {
+            if a != b {
+                changes.push((x, y, b));
+            }
+        }
+    }
+    changes
+}
+
+fn apply_changes(before: &Grid, changes: &[(usize, usize, char)]) -> Result<Grid, String> {
+    let mut rows: Vec<Vec<char>> = before.rows.iter().map(|row| row.chars().collect()).collect();
+    for &(x, y, symbol) in changes {
+        let cell = rows.get_mut(y)
+            .and_then(|row| row.get_mut(x))
+            .ok_or_else(|| format!("change outside the board: {} {}", x, y))?;
+        *cell = symbol;
+    }
+    let rows = rows.into_iter().map(|row| row.into_iter().collect()).collect();
+    Ok(Grid { width: before.width, height: before.height, rows })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME_LOG: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../logs/game_log.txt"));
+
+    #[test]
+    fn test_jsonl_round_trip() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        let jsonl = record.to_jsonl();
+        assert_eq!(GameRecord::from_jsonl(&jsonl).unwrap(), record);
+        // A line per turn plus the game and summary lines
+        assert_eq!(jsonl.lines().count(), record.turns.len() + 2);
+        assert!(jsonl.len() * 2 < GAME_LOG.len());
+    }
+
+    #[test]
+    fn test_jsonl_keeps_engine_errors() {
+        let text = "$$$ exec p1 : [/tmp/slow.sh]\n$$$ exec p2 : [linux_robots/wall_e]\n\
+            seed: 1792401245774128089\nError: Timeout for player1\n";
+        let record = GameRecord::from_text(text).unwrap();
+        assert_eq!(record.errors, vec![EngineError { turn: 0, message: "Error: Timeout for player1".to_string() }]);
+        assert_eq!(GameRecord::from_jsonl(&record.to_jsonl()).unwrap(), record);
+        assert_eq!(record.to_text(), text);
+    }
+}
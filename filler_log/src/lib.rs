@@ -0,0 +1,14 @@
+// Game logs of the filler engine as a typed `GameRecord`.
+//
+// A record is read from and written to two formats:
+// - the text log printed by the game engine (`$$$ exec` lines, `Anfield` and `Piece`
+//   blocks, `-> Answer` lines and the summary), see `text.rs`
+// - a compact line-delimited JSON format where every turn only stores the cells that
+//   changed since the previous turn, see `jsonl.rs`
+
+mod jsonl;
+mod record;
+mod text;
+
+pub use record::*;
+pub use text::ParsedLog;
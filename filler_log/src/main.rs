@@ -0,0 +1,48 @@
+use std::env;
+use std::fs;
+use std::process;
+
+use filler_log::GameRecord;
+
+// Converts an engine log to the JSON lines format and back, the input format is detected
+// from its first character
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let (input, output) = match &args[1..] {
+        [input] => (input, None),
+        [input, flag, output] if flag == "-o" => (input, Some(output)),
+        _ => {
+            eprintln!("Usage: {} <game_log.txt | game_log.jsonl> [-o <output>]", args[0]);
+            process::exit(1);
+        }
+    };
+
+    let text = fs::read_to_string(input).unwrap_or_else(|err| {
+        eprintln!("Failed to open file {}: {}", input, err);
+        process::exit(1);
+    });
+
+    let converted = if text.trim_start().starts_with('{') {
+        match GameRecord::from_jsonl(&text) {
+            Ok(record) => record.to_text(),
+            Err(err) => {
+                eprintln!("{}: {}", input, err);
+                process::exit(1);
+            }
+        }
+    } else {
+        let parsed = GameRecord::from_text_lossy(text.lines().map(str::to_string));
+        if let Some(err) = parsed.truncated {
+            eprintln!("{}: log truncated: {}", input, err);
+        }
+        parsed.record.to_jsonl()
+    };
+
+    match output {
+        Some(output) => fs::write(output, converted).unwrap_or_else(|err| {
+            eprintln!("Failed to write {}: {}", output, err);
+            process::exit(1);
+        }),
+        None => print!("{}", converted),
+    }
+}
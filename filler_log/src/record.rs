@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+
+// Symbols used on the board: the latest piece of a player is lowercase
+pub const EMPTY: char = '.';
+pub const PIECE_CELL: char = 'O';
+pub const SYMBOLS: [(char, char); 2] = [('@', 'a'), ('$', 's')];
+
+// Rows of an `Anfield` board or of a `Piece`, every row holds `width` cells
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    pub rows: Vec<String>,
+}
+
+impl Grid {
+    pub fn new(rows: Vec<String>) -> Self {
+        let width = rows.first().map_or(0, |row| row.chars().count());
+        Grid { width, height: rows.len(), rows }
+    }
+
+    pub fn cells(&self) -> impl Iterator<Item = char> + '_ {
+        self.rows.iter().flat_map(|row| row.chars())
+    }
+
+    // Cell at column `x` of row `y`
+    pub fn get(&self, x: usize, y: usize) -> Option<char> {
+        self.rows.get(y)?.chars().nth(x)
+    }
+}
+
+// "-> Answer (@): 7 1", the coordinates are the top left corner of the piece
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answer {
+    pub player: usize,
+    pub x: i32,
+    pub y: i32,
+}
+
+// One move: the board before the move, the piece given to the player and its answer.
+// The answer is missing when the player did not respond.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Turn {
+    pub board: Grid,
+    pub piece: Grid,
+    pub answer: Option<Answer>,
+}
+
+impl Turn {
+    // Player the piece was given to, known only once it answered
+    pub fn player(&self) -> Option<usize> {
+        self.answer.map(|answer| answer.player)
+    }
+}
+
+// Line starting with "Error" printed by the engine, e.g. "Error: Timeout for player1".
+// `turn` is the number of turns logged before it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EngineError {
+    pub turn: usize,
+    pub message: String,
+}
+
+// Summary printed once the game is over
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GameResult {
+    pub scores: [u32; 2],
+    // None when the winner line is missing or not understood
+    pub winner: Option<usize>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GameRecord {
+    // Paths of the player 1 and player 2 executables, without the brackets
+    pub players: [String; 2],
+    pub turns: Vec<Turn>,
+    pub errors: Vec<EngineError>,
+    pub seed: Option<u64>,
+    pub result: Option<GameResult>,
+}
+
+impl GameRecord {
+    // The board before the first move
+    pub fn map(&self) -> Option<&Grid> {
+        self.turns.first().map(|turn| &turn.board)
+    }
+}
+
+// Player number (1 or 2) owning a board cell
+pub fn owner(cell: char) -> Option<usize> {
+    SYMBOLS
+        .iter()
+        .position(|&(old, new)| cell == old || cell == new)
+        .map(|i| i + 1)
+}
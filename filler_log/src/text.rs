@@ -0,0 +1,394 @@
+use std::fmt::Write;
+
+use crate::record::*;
+
+// Width the engine right-aligns the player paths to in the summary
+const SUMMARY_PATH_WIDTH: usize = 40;
+
+// Result of a tolerant read: everything up to the point where the log stops
+pub struct ParsedLog {
+    pub record: GameRecord,
+    // Why reading stopped early, None when the log is complete
+    pub truncated: Option<String>,
+}
+
+impl GameRecord {
+    // Reads an engine log, any line that is not part of the game is an error
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let parsed = parse(text.lines().map(str::to_string), true);
+        match parsed.truncated {
+            Some(err) => Err(err),
+            None => Ok(parsed.record),
+        }
+    }
+
+    // Reads as much of an engine log as possible: lines that are not part of the game (bot
+    // output on stderr) are skipped between the blocks, reading stops at the first block that
+    // is truncated or has a bad row
+    pub fn from_text_lossy<I: Iterator<Item = String>>(lines: I) -> ParsedLog {
+        parse(lines, false)
+    }
+
+    // Writes the record the way the engine prints it. The engine separates the turns with
+    // a varying number of blank lines, here it is always two. An error is written where it
+    // was printed: before the turn it precedes, or after the seed once the game is over.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let write_errors = |text: &mut String, on_turn: &dyn Fn(usize) -> bool| {
+            for error in self.errors.iter().filter(|error| on_turn(error.turn)) {
+                let _ = writeln!(text, "{}", error.message);
+            }
+        };
+        // Writing to a String cannot fail
+        for (i, path) in self.players.iter().enumerate() {
+            let _ = writeln!(text, "$$$ exec p{} : [{}]", i + 1, path);
+        }
+
+        for (i, turn) in self.turns.iter().enumerate() {
+            write_errors(&mut text, &|turn| turn == i);
+            let board = &turn.board;
+            let _ = writeln!(text, "Anfield {} {}:", board.width, board.height);
+            let columns: String = (0..board.width).map(|x| char::from(b'0' + (x % 10) as u8)).collect();
+            let _ = writeln!(text, "    {}", columns);
+            for (y, row) in board.rows.iter().enumerate() {
+                let _ = writeln!(text, "{:03} {}", y, row);
+            }
+
+            let piece = &turn.piece;
+            let _ = writeln!(text, "Piece {} {}:", piece.width, piece.height);
+            for row in &piece.rows {
+                let _ = writeln!(text, "{}", row);
+            }
+
+            if let Some(answer) = turn.answer {
+                let symbol = SYMBOLS[answer.player - 1].0;
+                let _ = writeln!(text, "-> Answer ({}): {} {}", symbol, answer.x, answer.y);
+            }
+            text.push_str("\n\n");
+        }
+
+        if let Some(seed) = self.seed {
+            let _ = writeln!(text, "seed: {}", seed);
+        }
+        write_errors(&mut text, &|turn| turn >= self.turns.len());
+        if let Some(result) = &self.result {
+            for (i, score) in result.scores.iter().enumerate() {
+                let _ = writeln!(text, "Player{} ({:>width$}): {}",
+                    i + 1, self.players[i], score, width = SUMMARY_PATH_WIDTH);
+            }
+            if let Some(winner) = result.winner {
+                let _ = writeln!(text, "Player{} won!", winner);
+            }
+        }
+        text
+    }
+}
+
+// Source lines with their line number, for error messages
+struct Lines<I> {
+    lines: I,
+    number: usize,
+}
+
+impl<I: Iterator<Item = String>> Lines<I> {
+    fn next(&mut self) -> Option<String> {
+        self.number += 1;
+        self.lines.next()
+    }
+}
+
+// In strict mode reading stops at the first line that is not part of the game as well, the
+// reason is returned as `truncated`
+fn parse<I: Iterator<Item = String>>(lines: I, strict: bool) -> ParsedLog {
+    let mut lines = Lines { lines, number: 0 };
+    let mut record = GameRecord::default();
+
+    let truncated = loop {
+        let Some(line) = lines.next() else {
+            break None;
+        };
+        let number = lines.number;
+
+        if line.trim().is_empty() {
+            continue;
+
+        } else if let Some(path) = line.strip_prefix("$$$ exec p1") {
+            record.players[0] = player_path(path);
+
+        } else if let Some(path) = line.strip_prefix("$$$ exec p2") {
+            record.players[1] = player_path(path);
+
+        } else if line.starts_with("Anfield") {
+            match read_turn(&line, &mut lines, strict) {
+                Ok(turn) => record.turns.push(turn),
+                Err(err) => break Some(err),
+            }
+
+        } else if line.starts_with("-> Answer") {
+            let answer = parse_answer(&line).map_err(|err| format!("line {}: {}", number, err));
+            match (record.turns.last_mut(), answer) {
+                (Some(turn), Ok(answer)) if turn.answer.is_none() => turn.answer = Some(answer),
+                _ if !strict => {}
+                (_, Err(err)) => break Some(err),
+                _ => break Some(format!("line {}: answer without a piece: {}", number, line)),
+            }
+
+        } else if line.starts_with("Error") {
+            let message = line.trim().to_string();
+            record.errors.push(EngineError { turn: record.turns.len(), message });
+
+        } else if let Some(seed) = line.strip_prefix("seed:") {
+            match seed.trim().parse() {
+                Ok(seed) => record.seed = Some(seed),
+                Err(_) if !strict => {}
+                Err(_) => break Some(format!("line {}: invalid seed: {}", number, line)),
+            }
+
+        } else if let Some((player, score)) = parse_score(&line) {
+            let result = record.result.get_or_insert(GameResult { scores: [0, 0], winner: None });
+            result.scores[player - 1] = score;
+
+        } else if let Some(winner) = parse_winner(&line) {
+            let result = record.result.get_or_insert(GameResult { scores: [0, 0], winner: None });
+            result.winner = Some(winner);
+
+        } else if strict {
+            break Some(format!("line {}: unexpected line: {}", number, line));
+        }
+    };
+
+    ParsedLog { record, truncated }
+}
+
+// " : [solution/my_robot/target/release/my_robot]" -> "solution/my_robot/target/release/my_robot"
+fn player_path(rest: &str) -> String {
+    let path = rest.split_once(':').map_or("", |(_, path)| path).trim();
+    path.strip_prefix('[')
+        .and_then(|path| path.strip_suffix(']'))
+        .unwrap_or(path)
+        .to_string()
+}
+
+// Reads the `Anfield` block starting with `header` and the `Piece` block following it
+fn read_turn<I: Iterator<Item = String>>(header: &str, lines: &mut Lines<I>, strict: bool) -> Result<Turn, String> {
+    let (width, height) = parse_size(header, "Anfield").map_err(|err| format!("line {}: {}", lines.number, err))?;
+    let mut rows = Vec::with_capacity(height);
+
+    // Inside the block a bad row ends the read even when tolerant: skipping it would fill the
+    // board with the rows of the next block
+    while rows.len() < height {
+        let line = lines.next()
+            .ok_or_else(|| format!("Unexpected end of input while reading row {}", rows.len()))?;
+        match board_row(&line, rows.len(), width, strict) {
+            Some(row) => rows.push(row.to_string()),
+            // The column numbers above the first row
+            None if rows.is_empty() && is_column_header(&line, width) => {}
+            None => return Err(format!("line {}: invalid row {}: {}", lines.number, rows.len(), line)),
+        }
+    }
+    let board = Grid { width, height, rows };
+
+    let piece_header = loop {
+        let line = lines.next()
+            .ok_or_else(|| "Unexpected end of input while reading piece".to_string())?;
+        if line.starts_with("Piece") {
+            break line;
+        } else if line.starts_with("Anfield") || (strict && !line.trim().is_empty()) {
+            return Err(format!("line {}: expected a piece: {}", lines.number, line));
+        }
+    };
+    let (width, height) = parse_size(&piece_header, "Piece").map_err(|err| format!("line {}: {}", lines.number, err))?;
+    let mut rows = Vec::with_capacity(height);
+
+    while rows.len() < height {
+        let line = lines.next()
+            .ok_or_else(|| format!("Unexpected end of input while reading piece row {}", rows.len()))?;
+        let row = line.trim_end();
+        if row.len() == width && row.chars().all(|c| c == EMPTY || c == PIECE_CELL) {
+            rows.push(row.to_string());
+        } else {
+            return Err(format!("line {}: invalid piece row {}: {}", lines.number, rows.len(), line));
+        }
+    }
+    let piece = Grid { width, height, rows };
+
+    Ok(Turn { board, piece, answer: None })
+}
+
+// "Anfield 20 15:" -> (20, 15)
+fn parse_size(header: &str, name: &str) -> Result<(usize, usize), String> {
+    let parts: Vec<&str> = header
+        .trim_end()
+        .trim_end_matches(':')
+        .split_whitespace()
+        .collect();
+    match parts[..] {
+        [first, width, height] if first == name => {
+            let width = width.parse().map_err(|_| format!("Invalid col count: {}", header))?;
+            let height = height.parse().map_err(|_| format!("Invalid row count: {}", header))?;
+            Ok((width, height))
+        }
+        _ => Err(format!("Invalid {} header: {}", name.to_lowercase(), header)),
+    }
+}
+
+// "002 ........aa.........." is row 2, the row number has 3 digits followed by a space.
+// A tolerant read ignores what follows the cells (bot output printed on the same line).
+fn board_row(line: &str, row: usize, width: usize, strict: bool) -> Option<&str> {
+    let cells = line.strip_prefix(&format!("{:03} ", row))?;
+    if strict {
+        let cells = cells.trim_end();
+        return (cells.len() == width && cells.is_ascii()).then_some(cells);
+    }
+    let is_row = cells.len() >= width && cells.is_char_boundary(width) && cells[..width].is_ascii();
+    is_row.then(|| &cells[..width])
+}
+
+fn is_column_header(line: &str, width: usize) -> bool {
+    let digits = line.trim();
+    line.starts_with("    ") && digits.len() == width && digits.bytes().all(|b| b.is_ascii_digit())
+}
+
+// "-> Answer (@): 7 1"
+fn parse_answer(line: &str) -> Result<Answer, String> {
+    let invalid = || format!("invalid answer: {}", line);
+    let (symbol, position) = line
+        .strip_prefix("-> Answer (")
+        .and_then(|rest| rest.split_once("): "))
+        .ok_or_else(invalid)?;
+    let player = SYMBOLS
+        .iter()
+        .position(|&(old, _)| symbol == old.to_string())
+        .ok_or_else(invalid)?
+        + 1;
+    let (x, y) = position.trim().split_once(' ').ok_or_else(invalid)?;
+    let x = x.trim().parse().map_err(|_| invalid())?;
+    let y = y.trim().parse().map_err(|_| invalid())?;
+    Ok(Answer { player, x, y })
+}
+
+// "Player2 (                 linux_robots/terminator): 52" -> (2, 52)
+fn parse_score(line: &str) -> Option<(usize, u32)> {
+    let rest = line.strip_prefix("Player")?;
+    let player = match rest.as_bytes().first()? {
+        b'1' => 1,
+        b'2' => 2,
+        _ => return None,
+    };
+    let (_, score) = rest.strip_prefix(&player.to_string())?.strip_prefix(" (")?.rsplit_once("): ")?;
+    Some((player, score.trim().parse().ok()?))
+}
+
+// "Player1 won!" -> 1
+fn parse_winner(line: &str) -> Option<usize> {
+    match line.trim() {
+        "Player1 won!" => Some(1),
+        "Player2 won!" => Some(2),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME_LOG: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../logs/game_log.txt"));
+
+    fn non_blank_lines(text: &str) -> Vec<&str> {
+        text.lines().filter(|line| !line.trim().is_empty()).collect()
+    }
+
+    #[test]
+    fn test_reads_game_log() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        assert_eq!(record.players[0], "solution/my_robot/target/release/my_robot");
+        assert_eq!(record.players[1], "linux_robots/terminator");
+        assert_eq!(record.turns.len(), 112);
+        assert_eq!(record.map().map(|map| (map.width, map.height)), Some((20, 15)));
+        assert_eq!(record.turns[0].answer, Some(Answer { player: 1, x: 7, y: 1 }));
+        assert_eq!(record.turns[1].piece.rows, vec![".OOO", "..O."]);
+        assert_eq!(record.seed, Some(1758654202459825665));
+        assert_eq!(record.result, Some(GameResult { scores: [215, 52], winner: Some(1) }));
+    }
+
+    #[test]
+    fn test_text_round_trip() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        let text = record.to_text();
+        // Only the number of blank lines between the turns differs from the engine output
+        assert_eq!(non_blank_lines(&text), non_blank_lines(GAME_LOG));
+        assert_eq!(GameRecord::from_text(&text).unwrap(), record);
+    }
+
+    #[test]
+    fn test_text_round_trip_keeps_the_turn_of_an_error() {
+        // As if the engine printed an error after the fifth answer and went on
+        let fifth = GAME_LOG.match_indices("-> Answer").nth(4).unwrap().0;
+        let end = fifth + GAME_LOG[fifth..].find('\n').unwrap() + 1;
+        let log = format!("{}Error: invalid answer of player1\n{}", &GAME_LOG[..end], &GAME_LOG[end..]);
+
+        let record = GameRecord::from_text(&log).unwrap();
+        assert_eq!(record.errors, vec![EngineError { turn: 5, message: "Error: invalid answer of player1".to_string() }]);
+        let text = record.to_text();
+        assert_eq!(non_blank_lines(&text), non_blank_lines(&log));
+        assert_eq!(GameRecord::from_text(&text).unwrap(), record);
+    }
+
+    #[test]
+    fn test_strict_read_reports_what_lossy_read_skips() {
+        let log = GAME_LOG.replacen("seed:", "seed: unknown\nseed:", 1);
+        assert!(GameRecord::from_text(&log).unwrap_err().contains("invalid seed"));
+        let lossy = GameRecord::from_text_lossy(log.lines().map(str::to_string));
+        assert_eq!(lossy.truncated, None);
+        assert_eq!(lossy.record, GameRecord::from_text(GAME_LOG).unwrap());
+    }
+
+    #[test]
+    fn test_lossy_read_skips_noise_between_blocks() {
+        let noisy = GAME_LOG
+            .replacen("Anfield", "bot debug\nAnfield", 1)
+            .replace("-> Answer (@): 7 1\n", "-> Answer (@): 7 1\nplaced at 7 1\n");
+        assert!(GameRecord::from_text(&noisy).is_err());
+
+        let lossy = GameRecord::from_text_lossy(noisy.lines().map(str::to_string));
+        assert!(lossy.truncated.is_none());
+        assert_eq!(lossy.record, GameRecord::from_text(GAME_LOG).unwrap());
+    }
+
+    #[test]
+    fn test_lossy_read_stops_at_noise_inside_a_block() {
+        let noisy = GAME_LOG.replacen("001 ....", "bot debug\n001 ....", 1);
+        assert!(GameRecord::from_text(&noisy).is_err());
+
+        let lossy = GameRecord::from_text_lossy(noisy.lines().map(str::to_string));
+        assert!(lossy.truncated.unwrap().contains("invalid row 1"));
+        assert!(lossy.record.turns.is_empty());
+    }
+
+    #[test]
+    fn test_lossy_read_stops_at_truncated_block() {
+        // Cut in the middle of the last board
+        let cut = GAME_LOG.rfind("Anfield").unwrap();
+        let truncated = &GAME_LOG[..cut + 60];
+        assert!(GameRecord::from_text(truncated).is_err());
+
+        let lossy = GameRecord::from_text_lossy(truncated.lines().map(str::to_string));
+        assert!(lossy.truncated.is_some());
+        assert_eq!(lossy.record.turns.len(), 111);
+        assert_eq!(lossy.record.result, None);
+    }
+
+    #[test]
+    fn test_lossy_read_does_not_splice_a_cut_board_with_the_next_one() {
+        // The third board loses its last rows, the next block follows right away
+        let third = GAME_LOG.match_indices("Anfield").nth(2).unwrap().0;
+        let cut = third + GAME_LOG[third..].find("010 ").unwrap();
+        let next = third + GAME_LOG[third..].find("Piece").unwrap();
+        let next = next + GAME_LOG[next..].find("Anfield").unwrap();
+        let spliced = format!("{}{}", &GAME_LOG[..cut], &GAME_LOG[next..]);
+
+        let lossy = GameRecord::from_text_lossy(spliced.lines().map(str::to_string));
+        assert!(lossy.truncated.unwrap().contains("invalid row 10"));
+        assert_eq!(lossy.record.turns.len(), 2);
+    }
+}
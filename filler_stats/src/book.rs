@@ -0,0 +1,148 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use filler_log::{owner, GameRecord, Grid};
+
+// Placements of the winner kept as waypoints
+pub const BOOK_MOVES: usize = 12;
+
+// Opening of one winning game, seen from the winner
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opening {
+    pub width: usize,
+    pub height: usize,
+    // Start cells of the winner and of the loser, as (y, x)
+    pub start: (usize, usize),
+    pub enemy_start: (usize, usize),
+    pub margin: i64,
+    // Center of each of the first BOOK_MOVES placements of the winner, as (y, x)
+    pub waypoints: Vec<(usize, usize)>,
+}
+
+// Opening of the winner of the game, None when the game has no winner or a player does not
+// start on a single cell
+pub fn opening(record: &GameRecord) -> Option<Opening> {
+    let result = record.result.as_ref()?;
+    let winner = result.winner?;
+    let first = &record.turns.first()?.board;
+    let start = single_cell(first, winner)?;
+    let enemy_start = single_cell(first, 3 - winner)?;
+
+    let mut waypoints = Vec::new();
+    for (before, after) in record.turns.iter().zip(record.turns.iter().skip(1)) {
+        let placed = placed_cells(&before.board, &after.board, winner);
+        if placed.is_empty() {
+            continue;
+        }
+        let count = placed.len();
+        let (y, x) = placed.iter().fold((0, 0), |(y, x), &(py, px)| (y + py, x + px));
+        waypoints.push((y / count, x / count));
+        if waypoints.len() == BOOK_MOVES {
+            break;
+        }
+    }
+
+    Some(Opening {
+        width: first.width,
+        height: first.height,
+        start,
+        enemy_start,
+        margin: result.scores[winner - 1] as i64 - result.scores[2 - winner] as i64,
+        waypoints,
+    })
+}
+
+fn cells_of(board: &Grid, player: usize) -> Vec<(usize, usize)> {
+    let mut cells = Vec::new();
+    for (y, row) in board.rows.iter().enumerate() {
+        for (x, cell) in row.chars().enumerate() {
+            if owner(cell) == Some(player) {
+                cells.push((y, x));
+            }
+        }
+    }
+    cells
+}
+
+fn single_cell(board: &Grid, player: usize) -> Option<(usize, usize)> {
+    match cells_of(board, player)[..] {
+        [cell] => Some(cell),
+        _ => None,
+    }
+}
+
+fn placed_cells(before: &Grid, after: &Grid, player: usize) -> Vec<(usize, usize)> {
+    cells_of(after, player)
+        .into_iter()
+        .filter(|&(y, x)| before.get(x, y).and_then(owner) != Some(player))
+        .collect()
+}
+
+// Board size and starts of an opening
+type BookKey = (usize, usize, (usize, usize), (usize, usize));
+
+// Book of the openings with the largest margin for each board size and pair of starts, in
+// the format embedded in my_robot:
+// width height start_y start_x enemy_start_y enemy_start_x: y,x y,x ...
+pub fn opening_book(openings: &[Opening]) -> String {
+    let mut best: BTreeMap<BookKey, &Opening> = BTreeMap::new();
+    for opening in openings {
+        let key = (opening.width, opening.height, opening.start, opening.enemy_start);
+        if !matches!(best.get(&key), Some(other) if other.margin >= opening.margin) {
+            best.insert(key, opening);
+        }
+    }
+
+    let mut book = String::new();
+    let _ = writeln!(book, "# Opening book, generated by `filler-stats <logs> --opening-book` from winning games");
+    let _ = writeln!(book, "# width height start_y start_x enemy_start_y enemy_start_x: waypoints as y,x");
+    for opening in best.values() {
+        let waypoints: Vec<String> = opening.waypoints.iter().map(|(y, x)| format!("{},{}", y, x)).collect();
+        let _ = writeln!(
+            book,
+            "{} {} {} {} {} {}: {}",
+            opening.width,
+            opening.height,
+            opening.start.0,
+            opening.start.1,
+            opening.enemy_start.0,
+            opening.enemy_start.1,
+            waypoints.join(" ")
+        );
+    }
+    book
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME_LOG: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../logs/game_log.txt"));
+
+    #[test]
+    fn test_opening_of_game_log() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        let opening = opening(&record).unwrap();
+        assert_eq!((opening.width, opening.height), (20, 15));
+        // Player 1 won from the @ of map00
+        assert_eq!(opening.start, (2, 9));
+        assert_eq!(opening.enemy_start, (12, 9));
+        assert_eq!(opening.margin, 163);
+        assert_eq!(opening.waypoints.len(), BOOK_MOVES);
+    }
+
+    #[test]
+    fn test_book_keeps_the_largest_win() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        let opening = opening(&record).unwrap();
+        let mut worse = opening.clone();
+        worse.margin = 10;
+        worse.waypoints = vec![(0, 0)];
+
+        let book = opening_book(&[worse, opening.clone()]);
+        let lines: Vec<&str> = book.lines().filter(|line| !line.starts_with('#')).collect();
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("20 15 2 9 12 9: "));
+        assert_eq!(lines[0].split(' ').count(), 6 + BOOK_MOVES);
+    }
+}
@@ -0,0 +1,103 @@
+mod book;
+mod stats;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process;
+
+use filler_log::GameRecord;
+use book::{opening, opening_book};
+use stats::{game_stats, report, Maps};
+
+struct Options {
+    dirs: Vec<PathBuf>,
+    bot: String,
+    maps: PathBuf,
+    // Print the opening book of the winning games instead of the stats
+    opening_book: bool,
+}
+
+fn parse_args(args: &[String]) -> Option<Options> {
+    let mut args = args.iter().skip(1);
+    let mut options = Options {
+        dirs: Vec::new(),
+        bot: "my_robot".to_string(),
+        maps: PathBuf::from("maps"),
+        opening_book: false,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--bot" => options.bot = args.next()?.clone(),
+            "--maps" => options.maps = PathBuf::from(args.next()?),
+            "--opening-book" => options.opening_book = true,
+            _ if arg.starts_with("--") => return None,
+            _ => options.dirs.push(PathBuf::from(arg)),
+        }
+    }
+    if options.dirs.is_empty() {
+        return None;
+    }
+    Some(options)
+}
+
+// Reads an engine log or its JSON lines conversion
+fn read_record(path: &Path) -> Result<GameRecord, String> {
+    let text = fs::read_to_string(path).map_err(|err| err.to_string())?;
+    if text.trim_start().starts_with('{') {
+        GameRecord::from_jsonl(&text)
+    } else {
+        Ok(GameRecord::from_text_lossy(text.lines().map(str::to_string)).record)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let Some(options) = parse_args(&args) else {
+        eprintln!("Usage: {} <logs_dir>... [--bot <name in player path>] [--maps <maps_dir>] [--opening-book]", args[0]);
+        process::exit(1);
+    };
+
+    // The logs of each directory in turn, sorted by name
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for dir in &options.dirs {
+        let entries = fs::read_dir(dir).unwrap_or_else(|err| {
+            eprintln!("Failed to open directory {}: {}", dir.display(), err);
+            process::exit(1);
+        });
+        let mut dir_paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect();
+        dir_paths.sort();
+        paths.extend(dir_paths);
+    }
+
+    if options.opening_book {
+        let openings: Vec<_> = paths.iter()
+            .filter_map(|path| read_record(path).ok())
+            .filter_map(|record| opening(&record))
+            .collect();
+        print!("{}", opening_book(&openings));
+        return;
+    }
+
+    let maps = Maps::load(&options.maps);
+    let mut games = Vec::new();
+    for path in &paths {
+        let stats = read_record(path).map(|record| game_stats(&record, &options.bot, &maps));
+        match stats {
+            Ok(Some(stats)) => games.push(stats),
+            Ok(None) => eprintln!("{}: skipped, no game of {}", path.display(), options.bot),
+            Err(err) => eprintln!("{}: skipped, {}", path.display(), err),
+        }
+    }
+
+    if games.is_empty() {
+        let dirs: Vec<String> = options.dirs.iter().map(|dir| dir.display().to_string()).collect();
+        eprintln!("No game of {} in {}", options.bot, dirs.join(", "));
+        process::exit(1);
+    }
+    print!("{}", report(&games));
+}
@@ -0,0 +1,277 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+
+use filler_log::{owner, GameRecord, Grid};
+
+// Metrics of one game, seen from our bot
+#[derive(Debug, Clone, PartialEq)]
+pub struct GameStats {
+    pub opponent: String,
+    pub map: String,
+    pub seat: usize,
+    // None when the log has no result (crash, timeout, truncated log)
+    pub won: Option<bool>,
+    pub territory: Option<u32>,
+    pub margin: Option<i64>,
+    // Turns played by our bot
+    pub length: usize,
+    // First turn where cells of both players touch, None if they never did
+    pub first_contact: Option<usize>,
+    // Turns played by our bot before it first got stuck, its number of turns if it never did
+    pub turns_before_stuck: usize,
+}
+
+// Maps the games are played on, to name the map of a log from its first board
+pub struct Maps(Vec<(String, Vec<String>)>);
+
+impl Maps {
+    // Reads every file of `dir` as a map, a missing directory gives no known map
+    pub fn load(dir: &Path) -> Self {
+        let mut maps: Vec<(String, Vec<String>)> = std::fs::read_dir(dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let rows = std::fs::read_to_string(entry.path()).ok()?;
+                let rows = rows.lines().map(str::to_string).collect();
+                Some((entry.file_name().to_string_lossy().into_owned(), rows))
+            })
+            .collect();
+        maps.sort();
+        Maps(maps)
+    }
+
+    // Name of the map the board starts from, its size when it is not a known map
+    pub fn name(&self, board: &Grid) -> String {
+        self.0.iter()
+            .find(|(_, rows)| *rows == board.rows)
+            .map(|(name, _)| name.clone())
+            .unwrap_or_else(|| format!("{}x{}", board.width, board.height))
+    }
+}
+
+// Stats of a game where one of the players path contains `bot`, None when our bot is
+// not exactly one of the players or no turn was played
+pub fn game_stats(record: &GameRecord, bot: &str, maps: &Maps) -> Option<GameStats> {
+    let seat = match (record.players[0].contains(bot), record.players[1].contains(bot)) {
+        (true, false) => 1,
+        (false, true) => 2,
+        _ => return None,
+    };
+    let map = record.map()?;
+    let opponent = &record.players[2 - seat];
+    let opponent = Path::new(opponent)
+        .file_name()
+        .map_or(opponent.clone(), |name| name.to_string_lossy().into_owned());
+
+    let result = record.result.as_ref();
+    let ours = result.map(|result| result.scores[seat - 1]);
+    let theirs = result.map(|result| result.scores[2 - seat]);
+
+    let first_contact = record.turns.iter().position(|turn| players_touch(&turn.board));
+    let our_turns: Vec<usize> = (0..record.turns.len()).filter(|&i| record.turns[i].player() == Some(seat)).collect();
+    let turns_before_stuck = our_turns.iter()
+        .position(|&i| is_stuck(record, i, seat, our_turns.last() == Some(&i)))
+        .unwrap_or(our_turns.len());
+
+    Some(GameStats {
+        opponent,
+        map: maps.name(map),
+        seat,
+        won: result.and_then(|result| result.winner).map(|winner| winner == seat),
+        territory: ours,
+        margin: ours.zip(theirs).map(|(ours, theirs)| ours as i64 - theirs as i64),
+        length: our_turns.len(),
+        first_contact,
+        turns_before_stuck,
+    })
+}
+
+// True when turn `i` of `seat` answered "0 0" without placing the piece: the next board has no
+// new cell of the seat, or the seat never answers again. (0, 0) can also be a legal placement.
+fn is_stuck(record: &GameRecord, i: usize, seat: usize, last: bool) -> bool {
+    let turn = &record.turns[i];
+    if !turn.answer.is_some_and(|answer| answer.x == 0 && answer.y == 0) {
+        return false;
+    }
+    let cells = |board: &Grid| board.cells().filter(|&cell| owner(cell) == Some(seat)).count();
+    last || record.turns.get(i + 1).is_some_and(|next| cells(&next.board) == cells(&turn.board))
+}
+
+// True when a cell of player 1 is next to a cell of player 2, diagonals included
+fn players_touch(board: &Grid) -> bool {
+    let rows: Vec<Vec<Option<usize>>> = board.rows.iter()
+        .map(|row| row.chars().map(owner).collect())
+        .collect();
+    for (y, row) in rows.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            if *cell != Some(1) {
+                continue;
+            }
+            let columns = x.saturating_sub(1)..=(x + 1).min(row.len() - 1);
+            let neighbours = &rows[y.saturating_sub(1)..=(y + 1).min(rows.len() - 1)];
+            if neighbours.iter().any(|other| other[columns.clone()].contains(&Some(2))) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+// Averages of a group of games, the games without a result only count for the length,
+// contact and stuck columns
+#[derive(Debug, Default)]
+struct Summary {
+    games: usize,
+    finished: usize,
+    wins: usize,
+    territory: u64,
+    margin: i64,
+    length: usize,
+    contacts: usize,
+    first_contact: usize,
+    turns_before_stuck: usize,
+}
+
+impl Summary {
+    fn add(&mut self, game: &GameStats) {
+        self.games += 1;
+        if let (Some(won), Some(territory), Some(margin)) = (game.won, game.territory, game.margin) {
+            self.finished += 1;
+            self.wins += won as usize;
+            self.territory += territory as u64;
+            self.margin += margin;
+        }
+        self.length += game.length;
+        if let Some(turn) = game.first_contact {
+            self.contacts += 1;
+            self.first_contact += turn;
+        }
+        self.turns_before_stuck += game.turns_before_stuck;
+    }
+
+    fn row(&self, opponent: &str, map: &str, seat: &str) -> String {
+        let average = |total: f64, count: usize| match count {
+            0 => "-".to_string(),
+            _ => format!("{:.1}", total / count as f64),
+        };
+        format!(
+            "{:<16} {:<8} {:<5} {:>5} {:>6} {:>9} {:>8} {:>7} {:>8} {:>7}",
+            opponent,
+            map,
+            seat,
+            self.games,
+            average(self.wins as f64 * 100.0, self.finished),
+            average(self.territory as f64, self.finished),
+            average(self.margin as f64, self.finished),
+            average(self.length as f64, self.games),
+            average(self.first_contact as f64, self.contacts),
+            average(self.turns_before_stuck as f64, self.games),
+        )
+    }
+}
+
+// One row per opponent, map and seat, then a row for all games
+pub fn report(games: &[GameStats]) -> String {
+    let mut groups: BTreeMap<(&str, &str, usize), Summary> = BTreeMap::new();
+    let mut total = Summary::default();
+    for game in games {
+        groups.entry((&game.opponent, &game.map, game.seat)).or_default().add(game);
+        total.add(game);
+    }
+
+    let mut report = String::new();
+    // Writing to a String cannot fail
+    let _ = writeln!(
+        report,
+        "{:<16} {:<8} {:<5} {:>5} {:>6} {:>9} {:>8} {:>7} {:>8} {:>7}",
+        "opponent", "map", "seat", "games", "win%", "territory", "margin", "length", "contact", "stuck"
+    );
+    for ((opponent, map, seat), summary) in &groups {
+        let _ = writeln!(report, "{}", summary.row(opponent, map, &format!("p{}", seat)));
+    }
+    let _ = writeln!(report, "{}", total.row("all", "", ""));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use filler_log::{Answer, Turn};
+
+    const GAME_LOG: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../logs/game_log.txt"));
+
+    fn maps() -> Maps {
+        Maps::load(&Path::new(env!("CARGO_MANIFEST_DIR")).join("../maps"))
+    }
+
+    #[test]
+    fn test_stats_of_game_log() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        let stats = game_stats(&record, "my_robot", &maps()).unwrap();
+        assert_eq!(stats.opponent, "terminator");
+        assert_eq!(stats.map, "map00");
+        assert_eq!(stats.seat, 1);
+        assert_eq!(stats.won, Some(true));
+        assert_eq!(stats.territory, Some(215));
+        assert_eq!(stats.margin, Some(163));
+        assert_eq!(stats.length, 84);
+        // Our last answer is the "0 0" of a piece that fits nowhere
+        assert_eq!(stats.turns_before_stuck, 83);
+
+        let contact = stats.first_contact.unwrap();
+        assert!(players_touch(&record.turns[contact].board));
+        assert!(!players_touch(&record.turns[contact - 1].board));
+    }
+
+    #[test]
+    fn test_a_placement_at_0_0_is_not_stuck() {
+        let turn = |rows: [&str; 3], piece: &str, player: usize, (x, y): (i32, i32)| Turn {
+            board: Grid::new(rows.iter().map(|row| row.to_string()).collect()),
+            piece: Grid::new(vec![piece.to_string()]),
+            answer: Some(Answer { player, x, y }),
+        };
+        let record = GameRecord {
+            players: ["my_robot".to_string(), "terminator".to_string()],
+            turns: vec![
+                // Our domino at (0, 0) takes the cell right of our start
+                turn(["@..", "...", "..$"], "OO", 1, (0, 0)),
+                turn(["@a.", "...", "..$"], "O", 2, (2, 1)),
+                // Then nothing fits and the board does not change
+                turn(["@@.", "..s", "..$"], "OO", 1, (0, 0)),
+                turn(["@@.", "..s", "..$"], "O", 2, (1, 2)),
+            ],
+            ..GameRecord::default()
+        };
+        let stats = game_stats(&record, "my_robot", &maps()).unwrap();
+        assert_eq!((stats.length, stats.turns_before_stuck), (2, 1));
+    }
+
+    #[test]
+    fn test_other_players_are_skipped() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        assert_eq!(game_stats(&record, "wall_e", &maps()), None);
+        // Both players match
+        assert_eq!(game_stats(&record, "_", &maps()), None);
+    }
+
+    #[test]
+    fn test_unknown_map_is_named_by_size() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        let stats = game_stats(&record, "my_robot", &Maps(Vec::new())).unwrap();
+        assert_eq!(stats.map, "20x15");
+    }
+
+    #[test]
+    fn test_report_has_group_and_total_rows() {
+        let record = GameRecord::from_text(GAME_LOG).unwrap();
+        let stats = game_stats(&record, "my_robot", &maps()).unwrap();
+        let report = report(&[stats.clone(), stats]);
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[1].starts_with("terminator       map00    p1"));
+        assert!(lines[2].starts_with("all"));
+        assert!(lines[2].contains(" 100.0 "));
+    }
+}
@@ -0,0 +1,260 @@
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+use crate::visualizer::*;
+
+// Page with the player script, the game data replaces the `{{data}}` placeholder
+const REPLAY_TEMPLATE: &str = include_str!("replay.html");
+
+// Geometry of the SVG snapshot, in SVG units
+const SVG_MARGIN: usize = 20;
+const SVG_CELL: usize = 10;
+const SVG_PIECE_CELL: usize = 12;
+const SVG_PANEL_WIDTH: usize = 320;
+const SVG_LINE: usize = 22;
+// Outline of the cells added by the last placed piece
+const HIGHLIGHT_COLOR: (u8, u8, u8) = (255, 215, 0);
+
+// Cell symbols the replay page knows a color for, everything else gets the default color
+const SYMBOLS: [char; 5] = ['.', 'a', '@', 's', '$'];
+
+// Writes a single HTML file that replays the game in a browser without anything else
+// installed and without loading anything from the network
+pub fn html(visualizer: &Visualizer, title: &str, path: &str) -> Result<(), String> {
+    let data = replay_data(visualizer).to_string();
+    // The data sits inside a <script> tag, so a "</script>" in a player path must not end it
+    let data = data.replace("</", "<\\/");
+    let page = REPLAY_TEMPLATE
+        .replace("{{title}}", &escape_html(title))
+        .replace("{{data}}", &data);
+    fs::write(path, page).map_err(|err| format!("Failed to write {}: {}", path, err))
+}
+
+// Writes one turn (0-based) as an SVG image: the board, the cells of the last placed
+// piece outlined, the scores and the piece to place
+pub fn svg(visualizer: &Visualizer, turn: usize, path: &str) -> Result<(), String> {
+    let image = svg_image(visualizer, turn.min(visualizer.fields.len() - 1));
+    fs::write(path, image).map_err(|err| format!("Failed to write {}: {}", path, err))
+}
+
+// "logs/game_log.txt" at turn 41 becomes "logs/game_log_turn42.svg", turns are 1-based
+// like in the side panel
+pub fn svg_filename(log_filename: &str, turn: usize) -> String {
+    let path = Path::new(log_filename);
+    let stem = path.file_stem().map(|stem| stem.to_string_lossy()).unwrap_or_default();
+    path.with_file_name(format!("{}_turn{}.svg", stem, turn + 1))
+        .to_string_lossy()
+        .into_owned()
+}
+
+fn svg_image(visualizer: &Visualizer, turn: usize) -> String {
+    let field = &visualizer.fields[turn];
+    let previous = &visualizer.fields[turn.saturating_sub(1)];
+    let board_width = field.size.width * SVG_CELL;
+    let board_height = field.size.height * SVG_CELL;
+    let panel_x = SVG_MARGIN * 2 + board_width;
+    let piece_height = visualizer.pieces.get(turn).map_or(0, |(_, piece)| piece.size.height);
+    let width = panel_x + SVG_PANEL_WIDTH;
+    let height = (board_height + SVG_MARGIN * 2).max(SVG_MARGIN * 2 + SVG_LINE * 7 + piece_height * SVG_PIECE_CELL);
+
+    let mut svg = String::new();
+    // Writing to a String cannot fail
+    let _ = writeln!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}" font-family="monospace" font-size="14">"#,
+        w = width, h = height);
+    let _ = writeln!(svg, r#"<rect width="{}" height="{}" fill="black"/>"#, width, height);
+
+    // Cells are drawn one unit smaller than the grid so the black background shows the grid lines
+    let _ = writeln!(svg, r#"<g transform="translate({m} {m})">"#, m = SVG_MARGIN);
+    for (row_idx, row) in field.cells.iter().enumerate() {
+        for (col_idx, &ch) in row.iter().enumerate() {
+            let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}"/>"#,
+                col_idx * SVG_CELL, row_idx * SVG_CELL, hex_color(cell_color(ch)), s = SVG_CELL - 1);
+        }
+    }
+    let placed = field.cells.iter().flatten().zip(previous.cells.iter().flatten())
+        .enumerate()
+        .filter(|(_, (now, before))| **before == '.' && visualizer.owner(**now) != 0);
+    for (i, _) in placed {
+        let (col, row) = (i % field.size.width, i / field.size.width);
+        let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="none" stroke="{}" stroke-width="1.5"/>"#,
+            col * SVG_CELL, row * SVG_CELL, hex_color(HIGHLIGHT_COLOR), s = SVG_CELL - 1);
+    }
+    let _ = writeln!(svg, "</g>");
+
+    // Side panel
+    let (p1_score, p2_score) = visualizer.territory_at(turn);
+    let mut y = SVG_MARGIN + SVG_LINE;
+    let mut text = |svg: &mut String, content: &str, color: (u8, u8, u8)| {
+        let _ = writeln!(svg, r#"<text x="{}" y="{}" fill="{}" xml:space="preserve">{}</text>"#,
+            panel_x, y, hex_color(color), escape_html(content));
+        y += SVG_LINE;
+    };
+    let white = (255, 255, 255);
+    text(&mut svg, &format!("Turn: {} / {}", turn + 1, visualizer.fields.len()), white);
+    text(&mut svg, &format!("Player 1: {}", visualizer.players[0].path), P1_COLOR);
+    text(&mut svg, &format!("   score: {}", p1_score), P1_COLOR);
+    text(&mut svg, &format!("Player 2: {}", visualizer.players[1].path), P2_COLOR);
+    text(&mut svg, &format!("   score: {}", p2_score), P2_COLOR);
+
+    if let Some((player, piece)) = visualizer.pieces.get(turn) {
+        let fill = match player {
+            1 => P1_COLOR,
+            2 => P2_COLOR,
+            _ => white,
+        };
+        text(&mut svg, &format!("Player {} placing:", player), fill);
+        let piece_y = y - SVG_LINE / 2;
+        for (row_idx, row) in piece.cells.iter().enumerate() {
+            for (col_idx, &ch) in row.iter().enumerate() {
+                let cell_fill = if ch == 'O' { fill } else { cell_color('.') };
+                let _ = writeln!(svg, r#"<rect x="{}" y="{}" width="{s}" height="{s}" fill="{}"/>"#,
+                    panel_x + col_idx * SVG_PIECE_CELL, piece_y + row_idx * SVG_PIECE_CELL,
+                    hex_color(cell_fill), s = SVG_PIECE_CELL - 1);
+            }
+        }
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// File name of the log without its directory, used as page title
+pub fn title(filename: &str) -> String {
+    Path::new(filename)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| filename.to_string())
+}
+
+// The first board is stored whole, every following turn only holds the cells that changed
+// as a list of cell indexes and a string with their new symbols
+fn replay_data(visualizer: &Visualizer) -> Value {
+    let first = &visualizer.fields[0];
+    let board: String = first.cells.iter().flatten().collect();
+
+    let turns: Vec<Value> = (0..visualizer.fields.len())
+        .map(|turn| {
+            let (mut indexes, mut symbols) = (Vec::new(), String::new());
+            if turn > 0 {
+                let before = visualizer.fields[turn - 1].cells.iter().flatten();
+                let after = visualizer.fields[turn].cells.iter().flatten();
+                for (i, (b, a)) in before.zip(after).enumerate() {
+                    if b != a {
+                        indexes.push(i);
+                        symbols.push(*a);
+                    }
+                }
+            }
+            let (p1_score, p2_score) = visualizer.territory_at(turn);
+            let (player, piece) = match visualizer.pieces.get(turn) {
+                Some((player, piece)) => {
+                    let rows: Vec<String> = piece.cells.iter().map(|row| row.iter().collect()).collect();
+                    (*player, rows)
+                }
+                None => (0, Vec::new()),
+            };
+            let events: Vec<&str> = visualizer.events.iter()
+                .filter(|(event_turn, _)| *event_turn == turn)
+                .map(|(_, event)| event.as_str())
+                .collect();
+
+            json!({
+                "cells": indexes,
+                "symbols": symbols,
+                "score": [p1_score, p2_score],
+                "player": player,
+                "piece": piece,
+                "events": events,
+            })
+        })
+        .collect();
+
+    let colors: serde_json::Map<String, Value> = SYMBOLS.iter()
+        .map(|&ch| (ch.to_string(), Value::from(hex_color(cell_color(ch)))))
+        .collect();
+
+    json!({
+        "width": first.size.width,
+        "height": first.size.height,
+        "players": [&visualizer.players[0].path, &visualizer.players[1].path],
+        "playerColors": [hex_color(P1_COLOR), hex_color(P2_COLOR)],
+        "colors": colors,
+        "defaultColor": hex_color(cell_color(' ')),
+        "board": board,
+        "turns": turns,
+    })
+}
+
+fn hex_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME_LOG: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../logs/game_log.txt"));
+
+    fn load(text: &str) -> Visualizer {
+        Visualizer::new(text.lines().map(|line| Ok(line.to_string())))
+    }
+
+    #[test]
+    fn test_svg_filename() {
+        assert_eq!(svg_filename("logs/game_log.txt", 41), "logs/game_log_turn42.svg");
+        assert_eq!(svg_filename("game", 0), "game_turn1.svg");
+    }
+
+    #[test]
+    fn test_replay_data_rebuilds_every_board() {
+        let visualizer = load(GAME_LOG);
+        let data = replay_data(&visualizer);
+        let turns = data["turns"].as_array().unwrap();
+        assert_eq!(turns.len(), visualizer.fields.len());
+        assert_eq!((data["width"].as_u64(), data["height"].as_u64()), (Some(20), Some(15)));
+
+        // Applying the changed cells of every turn to the first board, as the page does
+        let mut board: Vec<char> = data["board"].as_str().unwrap().chars().collect();
+        for (turn, (data, field)) in turns.iter().zip(&visualizer.fields).enumerate() {
+            let cells = data["cells"].as_array().unwrap();
+            for (i, symbol) in cells.iter().zip(data["symbols"].as_str().unwrap().chars()) {
+                board[i.as_u64().unwrap() as usize] = symbol;
+            }
+            assert_eq!(board, field.cells.iter().flatten().copied().collect::<Vec<_>>(), "turn {}", turn);
+            let (p1, p2) = visualizer.territory_at(turn);
+            assert_eq!(data["score"], json!([p1, p2]));
+        }
+        assert_eq!(turns[0]["player"], json!(1));
+        assert_eq!(turns[0]["piece"].as_array().map(Vec::len), Some(visualizer.pieces[0].1.size.height));
+    }
+
+    #[test]
+    fn test_svg_image_outlines_the_last_piece() {
+        let visualizer = load(GAME_LOG);
+        let outlines = |turn| svg_image(&visualizer, turn).matches(&format!(r#"stroke="{}""#, hex_color(HIGHLIGHT_COLOR))).count();
+
+        let image = svg_image(&visualizer, 1);
+        assert!(image.starts_with("<svg ") && image.ends_with("</svg>\n"));
+        assert!(image.contains(&format!("Turn: 2 / {}", visualizer.fields.len())));
+        // Nothing placed before the first board, a piece of at least one block after it
+        assert_eq!(outlines(0), 0);
+        assert!(outlines(1) > 0);
+    }
+
+    #[test]
+    fn test_player_paths_are_escaped() {
+        let mut visualizer = load(GAME_LOG);
+        visualizer.players[0].path = "<bot> & co".to_string();
+        assert!(svg_image(&visualizer, 0).contains("&lt;bot&gt; &amp; co"));
+    }
+}
@@ -1,4 +1,3 @@
-use std::io::Error;
 use crate::grid::{Size, Grid};
 
 #[derive(Debug, Clone)]
@@ -13,41 +12,10 @@ pub struct _Pos {
     pub x: usize,
 }
 
-impl Field {
-    pub fn new(header: &str) -> Self {
-
-        let parts: Vec<&str> = header
-            .trim_end_matches(':')
-            .split_whitespace()
-            .collect();
-        let col_count: usize = parts[1].parse().expect("Invalid col count");
-        let row_count: usize = parts[2].parse().expect("Invalid row count");
-
-        let cells = vec![vec!['.'; col_count]; row_count];
-        Field { size: Size{ height: row_count, width: col_count }, cells }
-    }
-
-    pub fn update<I: Iterator<Item = Result<String, Error>>>(&mut self, lines: &mut I) {
-        let _ = lines.next(); // skip column headers
-
-        for r in 0..self.height() {
-            let line = match lines.next() {
-                Some(Ok(l)) => l.trim_end().to_string(),
-                _ => panic!("Unexpected end of input while reading row {}", r),
-            };
-
-            if line.chars().count() < 4 + self.width() {
-                panic!( "Invalid row {}: expected at least {} characters, got {}",
-                    r, 4 + self.width(), line.chars().count());
-            }
-
-            let row_data: Vec<char> = line[4..].chars().take(self.width()).collect();
-            if row_data.len() != self.width() {
-                panic!( "Row {} has incorrect number of columns: expected {}, got {}",
-                    r, self.width(), row_data.len());
-            }
-            self.cells_mut()[r] = row_data;
-        }
+impl From<&filler_log::Grid> for Field {
+    fn from(board: &filler_log::Grid) -> Self {
+        let cells = board.rows.iter().map(|row| row.chars().collect()).collect();
+        Field { size: Size { height: board.height, width: board.width }, cells }
     }
 }
 
@@ -55,7 +23,6 @@ impl Grid for Field {
     fn height(&self) -> usize { self.size.height }
     fn width(&self) -> usize { self.size.width }
     fn cells(&self) -> &Vec<Vec<char>> { &self.cells }
-    fn cells_mut(&mut self) -> &mut Vec<Vec<char>> { &mut self.cells }
 }
 use std::fmt;
 
@@ -63,4 +30,4 @@ impl fmt::Display for Field {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.print_grid())
     }
-}
\ No newline at end of file
+}
@@ -8,7 +8,6 @@ pub trait Grid {
     fn height(&self) -> usize;
     fn width(&self) -> usize;
     fn cells(&self) -> &Vec<Vec<char>>;
-    fn cells_mut(&mut self) -> &mut Vec<Vec<char>>;
 
     fn print_grid(&self) -> String {
         let mut output = String::new();
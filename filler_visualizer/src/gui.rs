@@ -0,0 +1,479 @@
+use std::time::Duration;
+
+use sdl2::event::{Event, WindowEvent};
+use sdl2::keyboard::Keycode;
+use sdl2::mouse::MouseButton;
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+use sdl2::render::{BlendMode, Canvas, TextureCreator};
+use sdl2::ttf::{Font, Sdl2TtfContext};
+use sdl2::video::{Window, WindowContext};
+use sdl2::EventPump;
+
+use crate::export;
+use crate::session::Session;
+use crate::visualizer::*;
+use crate::{load_session, Options};
+
+const WINDOW_HEIGHT: u32 = 900;
+// Width of the window for each game shown side by side
+const GAME_WIDTHS: [u32; 2] = [1200, 1800];
+
+// Opens the SDL window and runs until it is closed
+pub fn run(options: &Options) {
+    let width = GAME_WIDTHS[options.compare_filename.is_some() as usize];
+    let mut session = load_session(options, (width, WINDOW_HEIGHT));
+
+    // SDL2 Initialization
+    let (mut canvas, mut event_pump, ttf_context) = init_sdl2("Visualizer", width, WINDOW_HEIGHT);
+
+    
+    // Create texture creator for font rendering
+    let texture_creator = canvas.texture_creator();
+
+    // Load font once
+    let font_path = "assets/Roboto-Regular.ttf";
+    let font = ttf_context.load_font(font_path, 12).unwrap();
+
+    let mut mouse_pos = (0, 0);
+    'running: loop {
+        for event in event_pump.poll_iter() {
+            match event {
+                Event::Quit { .. }
+                | Event::KeyDown {
+                    keycode: Some(Keycode::Escape),
+                    ..
+                } => break 'running,
+
+                Event::KeyDown { keycode: Some(key), .. } => {
+                    match key {
+                        Keycode::Right => session.next_turn(),
+                        Keycode::Left => session.prev_turn(),
+                        Keycode::Up =>  session.first_turn(),
+                        Keycode::Down => session.last_turn(),
+                        Keycode::H => session.toggle_heatmap(),
+                        Keycode::D => session.toggle_diff(),
+                        Keycode::F => session.fit_to_window(),
+                        Keycode::S => save_snapshots(&session),
+                        Keycode::R => {
+                            let show_diff = session.show_diff;
+                            session = load_session(options, canvas.output_size().unwrap_or((width, WINDOW_HEIGHT)));
+                            session.show_diff = show_diff;
+                        }
+                        _ => {} // Ignore other keys
+                    }
+                }
+
+                Event::MouseButtonDown { mouse_btn: MouseButton::Left, x, y, .. } => {
+                    session.mouse_down(x, y);
+                }
+                Event::MouseMotion { x, y, .. } => {
+                    mouse_pos = (x, y);
+                    session.mouse_move(x, y);
+                }
+                Event::MouseButtonUp { mouse_btn: MouseButton::Left, .. } => session.mouse_up(),
+                Event::MouseWheel { y, .. } => session.zoom_at_mouse(mouse_pos.0, mouse_pos.1, y),
+
+                Event::Window { win_event: WindowEvent::SizeChanged(width, height), .. } => {
+                    session.resize(width as u32, height as u32);
+                }
+
+                _ => {}
+            }
+        }
+
+        canvas.set_draw_color(Color::RGB(0, 0, 0));
+        canvas.clear();
+
+        session.draw(&mut canvas, &font, &texture_creator);
+
+        canvas.present();
+        std::thread::sleep(Duration::from_millis(16));
+    }
+}
+
+impl Visualizer {
+    pub fn draw(
+        &self,
+        canvas: &mut Canvas<Window>,
+        font: &Font,
+        texture_creator: &TextureCreator<WindowContext>,
+    ) {
+        let field = &self.fields[self.turn];
+        let layout = self.layout();
+        let panel_x = layout.panel_x;
+        // Leave a gap between cells only while they are big enough to still be visible
+        let margin = if layout.cell_size >= 4.0 { 1 } else { 0 };
+
+        let (p1_score, p2_score) = self.territory();
+        let p1_col = color(P1_COLOR);
+        let p2_col = color(P2_COLOR);
+
+        // Draw field grid, clipped to the board area when zoomed in
+        canvas.set_clip_rect(to_rect(layout.board_area));
+        for (row_idx, row) in field.cells.iter().enumerate() {
+            for (col_idx, &ch) in row.iter().enumerate() {
+                let cell = layout.cell_rect(col_idx, row_idx);
+                let rect = Rect::new(
+                    cell.x + margin,
+                    cell.y + margin,
+                    cell.width.saturating_sub(2 * margin as u32).max(1),
+                    cell.height.saturating_sub(2 * margin as u32).max(1),
+                );
+
+                canvas.set_draw_color(color(cell_color(ch)));
+                canvas.fill_rect(rect).ok();
+            }
+        }
+
+        if self.show_heatmap {
+            self.draw_heatmap(canvas, &layout);
+        }
+
+        // Outline the hovered cell
+        if let Some((col, row)) = self.hovered_cell {
+            canvas.set_draw_color(Color::RGB(255, 255, 255));
+            canvas.draw_rect(to_rect(layout.cell_rect(col, row))).ok();
+        }
+        canvas.set_clip_rect(None);
+
+        self.draw_timeline(canvas, &layout);
+
+        // Draw text: Player info & Turn
+        draw_text(canvas, texture_creator, font,
+            &format!("Turn: {} / {}", self.turn + 1, self.fields.len()),
+            panel_x, 50, Color { r: 255, g: 255, b: 255, a: 0 });
+
+        draw_text(canvas, texture_creator, font,
+            &format!("Player 1: {}", self.players[0].path),
+            panel_x, 110, p1_col);
+        draw_text(canvas, texture_creator, font,
+            &format!("   score: {}", p1_score),
+            panel_x, 140, p1_col);
+
+        draw_text(canvas, texture_creator, font,
+            &format!("Player 2: {}", self.players[1].path),
+            panel_x, 170, p2_col);
+        draw_text(canvas, texture_creator, font,
+            &format!("   score: {}", p2_score),
+            panel_x, 200, p2_col);
+
+
+        // Draw piece grid (100x100 max size)
+        if let Some((player, piece)) = self.pieces.get(self.turn) {
+            let px = panel_x;
+            let py = 250;
+
+            let col = match player {
+                1 => p1_col,
+                2 => p2_col,
+                _ => Color { r: 255, g: 255, b: 255, a: 0 },
+            };
+
+            draw_text(canvas, texture_creator, font,
+                &format!("Player {} placing: ", player),
+                panel_x, py, col);
+
+            let cell_size = (150 / piece.size.width.max(1))
+                                .min(150 / piece.size.height.max(1)).min(50);
+            let margin = 1;
+
+            for (row_idx, row) in piece.cells.iter().enumerate() {
+                for (col_idx, &ch) in row.iter().enumerate() {
+
+                    let x = px + col_idx as i32 * cell_size as i32;
+                    let y = py + 30 + row_idx as i32 * cell_size as i32;
+
+                    let rect = Rect::new(
+                        x + margin,
+                        y + margin,
+                        (cell_size as u32).saturating_sub(2 * margin as u32),
+                        (cell_size as u32).saturating_sub(2 * margin as u32),
+                    );
+
+                    let color = match ch {
+                        'O' => col,
+                        _   => Color::RGB(50, 50, 50),
+                    };
+
+                    canvas.set_draw_color(color);
+                    canvas.fill_rect(rect).ok();
+                }
+            }
+        }
+
+        // Draw info about the cell under the mouse
+        if let Some((col, row)) = self.hovered_cell {
+            let ch = field.cells[row][col];
+            let (owner, col_owner) = if self.players[0].is_mine(&ch) {
+                (format!("Player 1: {}", self.players[0].path), p1_col)
+            } else if self.players[1].is_mine(&ch) {
+                (format!("Player 2: {}", self.players[1].path), p2_col)
+            } else {
+                ("empty".to_string(), Color::RGB(255, 255, 255))
+            };
+            let claimed = match self.claimed_turn(col, row) {
+                Some(0) => "start".to_string(),
+                // Counted from 1 like the turn of the side panel
+                Some(t) if t <= self.turn => format!("turn {}", t + 1),
+                _ => "-".to_string(),
+            };
+
+            draw_text(canvas, texture_creator, font,
+                &format!("Cell x: {}  y: {}", col, row),
+                panel_x, 450, Color::RGB(255, 255, 255));
+            draw_text(canvas, texture_creator, font,
+                &format!("Owner: {}", owner),
+                panel_x, 480, col_owner);
+            draw_text(canvas, texture_creator, font,
+                &format!("Claimed: {}", claimed),
+                panel_x, 510, Color::RGB(255, 255, 255));
+        }
+
+        // Draw engine errors and rejected moves of the current turn
+        let events = self.events.iter().filter(|(turn, _)| *turn == self.turn);
+        for (i, (_, event)) in events.enumerate() {
+            draw_text(canvas, texture_creator, font,
+                event,
+                panel_x, 560 + 30 * i as i32, Color::RGB(255, 60, 60));
+        }
+
+        if self.show_heatmap {
+            self.draw_tooltip(canvas, font, texture_creator);
+        }
+    }
+
+    // Marks every cell whose owner differs from the same cell in the other game
+    pub fn draw_diff(&self, canvas: &mut Canvas<Window>, other: &Visualizer) {
+        let layout = self.layout();
+        let field = &self.fields[self.turn];
+        let other_field = &other.fields[other.turn];
+
+        canvas.set_clip_rect(to_rect(layout.board_area));
+        canvas.set_draw_color(Color::RGB(255, 215, 0));
+        for (row_idx, row) in field.cells.iter().enumerate() {
+            for (col_idx, &ch) in row.iter().enumerate() {
+                let other_ch = other_field.cells.get(row_idx).and_then(|row| row.get(col_idx));
+                if other_ch.map(|&c| other.owner(c)) != Some(self.owner(ch)) {
+                    canvas.draw_rect(to_rect(layout.cell_rect(col_idx, row_idx))).ok();
+                }
+            }
+        }
+        canvas.set_clip_rect(None);
+    }
+
+    // Colors the cell of every candidate from cold (low score) to hot (high score)
+    fn draw_heatmap(&self, canvas: &mut Canvas<Window>, layout: &Layout) {
+        let Some(trace_turn) = self.trace_turn() else {
+            return;
+        };
+        let min = trace_turn.candidates.iter().map(|c| c.score).min().unwrap_or(0);
+        let max = trace_turn.candidates.iter().map(|c| c.score).max().unwrap_or(0);
+
+        canvas.set_blend_mode(BlendMode::Blend);
+        for candidate in &trace_turn.candidates {
+            let Some((col, row)) = self.candidate_cell(candidate.x, candidate.y) else {
+                continue;
+            };
+            let heat = if max > min {
+                (candidate.score - min) as f32 / (max - min) as f32
+            } else {
+                1.0
+            };
+            canvas.set_draw_color(Color::RGBA(
+                (255.0 * heat) as u8,
+                (220.0 * heat) as u8,
+                (255.0 * (1.0 - heat)) as u8,
+                170,
+            ));
+            let cell = to_rect(layout.cell_rect(col, row));
+            canvas.fill_rect(cell).ok();
+
+            // Outline the chosen move
+            if (candidate.x, candidate.y) == trace_turn.answer {
+                canvas.set_draw_color(Color::RGB(255, 215, 0));
+                for inset in 0..2 {
+                    canvas.draw_rect(Rect::new(
+                        cell.x() + inset,
+                        cell.y() + inset,
+                        cell.width().saturating_sub(2 * inset as u32).max(1),
+                        cell.height().saturating_sub(2 * inset as u32).max(1),
+                    )).ok();
+                }
+            }
+        }
+        canvas.set_blend_mode(BlendMode::None);
+    }
+
+    // Score breakdown of the candidate under the mouse, drawn next to the cursor
+    fn draw_tooltip(
+        &self,
+        canvas: &mut Canvas<Window>,
+        font: &Font,
+        texture_creator: &TextureCreator<WindowContext>,
+    ) {
+        let (Some(trace_turn), Some(hovered)) = (self.trace_turn(), self.hovered_cell) else {
+            return;
+        };
+        let Some(candidate) = trace_turn.candidates.iter()
+            .find(|c| self.candidate_cell(c.x, c.y) == Some(hovered)) else {
+            return;
+        };
+
+        let mut lines = vec![
+            format!("Answer: {} {}", candidate.x, candidate.y),
+            format!("Score: {}", candidate.score),
+        ];
+        for (source, value) in &candidate.breakdown {
+            lines.push(format!("  {}: {}", source, value));
+        }
+
+        let line_height = 16;
+        let (x, y) = (self.mouse_pos.0 + 14, self.mouse_pos.1 + 14);
+        let background = Rect::new(x, y, 170, (lines.len() as i32 * line_height + 8) as u32);
+        canvas.set_draw_color(Color::RGB(20, 20, 20));
+        canvas.fill_rect(background).ok();
+        canvas.set_draw_color(Color::RGB(200, 200, 200));
+        canvas.draw_rect(background).ok();
+
+        for (i, line) in lines.iter().enumerate() {
+            draw_text(canvas, texture_creator, font,
+                line,
+                x + 6, y + 4 + i as i32 * line_height, Color::RGB(255, 255, 255));
+        }
+    }
+
+    fn draw_timeline(&self, canvas: &mut Canvas<Window>, layout: &Layout) {
+        let rect = to_rect(layout.timeline);
+        let last = self.fields.len().saturating_sub(1).max(1);
+
+        canvas.set_draw_color(Color::RGB(40, 40, 40));
+        canvas.fill_rect(rect).ok();
+
+        let progress = rect.width() as usize * self.turn / last;
+        canvas.set_draw_color(Color::RGB(120, 120, 120));
+        canvas.fill_rect(Rect::new(rect.x(), rect.y(), progress as u32, rect.height())).ok();
+
+        // Mark turns with events
+        canvas.set_draw_color(Color::RGB(255, 60, 60));
+        for (turn, _) in &self.events {
+            let x = rect.x() + (rect.width() as usize * turn / last) as i32;
+            canvas.fill_rect(Rect::new(x - 1, rect.y(), 2, rect.height())).ok();
+        }
+
+        // Handle at the current turn
+        canvas.set_draw_color(Color::RGB(255, 255, 255));
+        canvas.fill_rect(Rect::new(
+            rect.x() + progress as i32 - 2,
+            rect.y() - 3,
+            4,
+            rect.height() + 6,
+        )).ok();
+    }
+}
+
+impl Session {
+    pub fn draw(
+        &self,
+        canvas: &mut Canvas<Window>,
+        font: &Font,
+        texture_creator: &TextureCreator<WindowContext>,
+    ) {
+        for (_, game) in &self.games {
+            game.draw(canvas, font, texture_creator);
+        }
+        if self.games.len() < 2 {
+            return;
+        }
+
+        // Log names on top of each column and the territory delta between the first two runs
+        let white = Color::RGB(255, 255, 255);
+        for (name, game) in &self.games {
+            draw_text(canvas, texture_creator, font, name,
+                game.viewport.x + 50, 8, white);
+        }
+
+        let (name_a, game_a) = &self.games[0];
+        let (name_b, game_b) = &self.games[1];
+        if let Some((delta1, delta2)) = self.territory_delta() {
+            draw_text(canvas, texture_creator, font,
+                &format!("{} vs {}: Player 1 {:+}  Player 2 {:+}", name_b, name_a, delta1, delta2),
+                game_b.viewport.x + 50, 26, white);
+        }
+
+        if self.show_diff {
+            game_a.draw_diff(canvas, game_b);
+            game_b.draw_diff(canvas, game_a);
+        }
+    }
+}
+
+// Writes the current turn of every game as an SVG file next to its log
+fn save_snapshots(session: &Session) {
+    for (name, game) in &session.games {
+        let path = export::svg_filename(name, game.turn);
+        match export::svg(game, game.turn, &path) {
+            Ok(()) => println!("Snapshot written to {}", path),
+            Err(err) => eprintln!("{}", err),
+        }
+    }
+}
+
+fn to_rect(area: Area) -> Rect {
+    Rect::new(area.x, area.y, area.width, area.height)
+}
+
+fn color((r, g, b): (u8, u8, u8)) -> Color {
+    Color::RGB(r, g, b)
+}
+
+pub fn draw_text(
+    canvas: &mut Canvas<Window>,
+    texture_creator: &TextureCreator<WindowContext>,
+    font: &Font,
+    text: &str,
+    x: i32,
+    y: i32,
+    color: Color,
+) {
+    let surface = font
+        .render(text)
+        .blended(color)
+        .unwrap();
+
+    let texture = texture_creator
+        .create_texture_from_surface(&surface)
+        .unwrap();
+
+    let target = Rect::new(x, y, surface.width(), surface.height());
+    canvas.copy(&texture, None, Some(target)).unwrap();
+}
+
+fn init_sdl2(
+    title: &str,
+    width: u32,
+    height: u32,
+) -> (Canvas<Window>, EventPump, Sdl2TtfContext) {
+    let sdl_context = sdl2::init().expect("Failed to initialize SDL2");
+    let video_subsystem = sdl_context.video().expect("Failed to get video subsystem");
+    let ttf_context = sdl2::ttf::init().expect("Failed to initialize TTF");
+
+    let window = video_subsystem
+        .window(title, width, height)
+        .position_centered()
+        .resizable()
+        .build()
+        .expect("Failed to create window");
+
+    let canvas = window
+        .into_canvas()
+        .present_vsync()
+        .build()
+        .expect("Failed to create canvas");
+
+    let event_pump = sdl_context
+        .event_pump()
+        .expect("Failed to create event pump");
+
+    (canvas, event_pump, ttf_context)
+}
@@ -1,75 +1,116 @@
+// Window geometry and mouse handling are only used by the SDL renderer
+#![cfg_attr(not(feature = "gui"), allow(dead_code))]
+
 mod field;
 mod piece;
 mod player;
 mod grid;
 mod visualizer;
+mod trace;
+mod session;
+mod export;
+#[cfg(feature = "gui")]
+mod gui;
+mod tui;
 
 use std::env;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
-use std::time::Duration;
 
-use sdl2::event::Event;
-use sdl2::keyboard::Keycode;
-use sdl2::pixels::Color;
+pub struct Options {
+    pub filename: String,
+    pub trace_filename: Option<String>,
+    pub compare_filename: Option<String>,
+    pub tui: bool,
+    pub export_html: Option<String>,
+    pub export_svg: Option<String>,
+    pub turn: Option<usize>,
+}
+
+fn parse_args(args: &[String]) -> Option<Options> {
+    let mut args = args.iter().skip(1);
+    let mut options = Options {
+        filename: String::new(),
+        trace_filename: None,
+        compare_filename: None,
+        // Without the gui feature the terminal is the only renderer
+        tui: !cfg!(feature = "gui"),
+        export_html: None,
+        export_svg: None,
+        turn: None,
+    };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--trace" => options.trace_filename = Some(args.next()?.clone()),
+            "--compare" => options.compare_filename = Some(args.next()?.clone()),
+            "--tui" => options.tui = true,
+            "--export-html" => options.export_html = Some(args.next()?.clone()),
+            "--export-svg" => options.export_svg = Some(args.next()?.clone()),
+            // Turns are counted from 1 like in the side panel
+            "--turn" => options.turn = Some(args.next()?.parse::<usize>().ok()?.checked_sub(1)?),
+            _ if options.filename.is_empty() => options.filename = arg.clone(),
+            _ => return None,
+        }
+    }
+    if options.filename.is_empty() {
+        return None;
+    }
+    Some(options)
+}
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() < 2 {
-        eprintln!("Usage: {} <game_log.txt>", args[0]);
+    let Some(options) = parse_args(&args) else {
+        eprintln!("Usage: {} <game_log.txt> [--trace <trace.jsonl>] [--compare <other_game_log.txt>] [--tui] [--export-html <replay.html>] [--export-svg <turn.svg> [--turn <n>]]", args[0]);
+        std::process::exit(1);
+    };
+
+    if let Some(path) = &options.export_html {
+        let visualizer = load_visualizer(&options.filename, None);
+        if let Err(err) = export::html(&visualizer, &export::title(&options.filename), path) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        println!("Replay written to {}", path);
+    } else if let Some(path) = &options.export_svg {
+        let visualizer = load_visualizer(&options.filename, None);
+        let turn = options.turn.unwrap_or(visualizer.fields.len() - 1);
+        if let Err(err) = export::svg(&visualizer, turn, path) {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+        println!("Snapshot written to {}", path);
+    } else if !options.tui {
+        #[cfg(feature = "gui")]
+        gui::run(&options);
+    } else if let Err(err) = tui::run(&options) {
+        eprintln!("Terminal error: {}", err);
         std::process::exit(1);
     }
-    let filename = &args[1];
+}
+
+fn load_session(options: &Options, window_size: (u32, u32)) -> session::Session {
+    let mut games = vec![(
+        options.filename.clone(),
+        load_visualizer(&options.filename, options.trace_filename.as_deref()),
+    )];
+    if let Some(compare_filename) = &options.compare_filename {
+        games.push((compare_filename.clone(), load_visualizer(compare_filename, None)));
+    }
+    session::Session::new(games, window_size)
+}
 
+fn load_visualizer(filename: &str, trace_filename: Option<&str>) -> visualizer::Visualizer {
     let lines = read_lines_from_file(filename);
     let mut visualizer = visualizer::Visualizer::new(lines);
 
-    // SDL2 Initialization
-    let (mut canvas, mut event_pump, ttf_context) = init_sdl2("Visualizer", 1200, 900);
-
-    
-    // Create texture creator for font rendering
-    let texture_creator = canvas.texture_creator();
-
-    // Load font once
-    let font_path = "assets/Roboto-Regular.ttf";
-    let font = ttf_context.load_font(font_path, 12).unwrap();
-
-    'running: loop {
-        for event in event_pump.poll_iter() {
-            match event {
-                Event::Quit { .. }
-                | Event::KeyDown {
-                    keycode: Some(Keycode::Escape),
-                    ..
-                } => break 'running,
-
-                Event::KeyDown { keycode: Some(key), .. } => {
-                    match key {
-                        Keycode::Right => visualizer.next_turn(),
-                        Keycode::Left => visualizer.prev_turn(),
-                        Keycode::Up =>  visualizer.first_turn(),
-                        Keycode::Down => visualizer.last_turn(),
-                        Keycode::R => {
-                            let lines = read_lines_from_file(filename);
-                            visualizer = visualizer::Visualizer::new(lines);
-                        }
-                        _ => {} // Ignore other keys
-                    }
-                }
-
-                _ => {}
-            }
+    if let Some(trace_filename) = trace_filename {
+        match trace::Trace::load(trace_filename) {
+            Ok(trace) => visualizer.trace = Some(trace),
+            Err(err) => eprintln!("{}", err),
         }
-
-        canvas.set_draw_color(Color::RGB(0, 0, 0));
-        canvas.clear();
-
-        visualizer.draw(&mut canvas, &font, &texture_creator);
-
-        canvas.present();
-        std::thread::sleep(Duration::from_millis(16));
     }
+    visualizer
 }
 
 use std::io;
@@ -79,39 +120,7 @@ fn read_lines_from_file(filename: &str) -> impl Iterator<Item = Result<String, i
         std::process::exit(1);
     });
 
+    // Bytes that are not UTF-8 are replaced rather than failing the line
     let reader = BufReader::new(file);
-    reader.lines()
+    reader.split(b'\n').map(|line| line.map(|bytes| String::from_utf8_lossy(&bytes).trim_end_matches('\r').to_string()))
 }
-
-use sdl2::render::Canvas;
-use sdl2::video::Window;
-use sdl2::EventPump;
-use sdl2::ttf::Sdl2TtfContext;
-
-fn init_sdl2(
-    title: &str,
-    width: u32,
-    height: u32,
-) -> (Canvas<Window>, EventPump, Sdl2TtfContext) {
-    let sdl_context = sdl2::init().expect("Failed to initialize SDL2");
-    let video_subsystem = sdl_context.video().expect("Failed to get video subsystem");
-    let ttf_context = sdl2::ttf::init().expect("Failed to initialize TTF");
-
-    let window = video_subsystem
-        .window(title, width, height)
-        .position_centered()
-        .build()
-        .expect("Failed to create window");
-
-    let canvas = window
-        .into_canvas()
-        .present_vsync()
-        .build()
-        .expect("Failed to create canvas");
-
-    let event_pump = sdl_context
-        .event_pump()
-        .expect("Failed to create event pump");
-
-    (canvas, event_pump, ttf_context)
-}
\ No newline at end of file
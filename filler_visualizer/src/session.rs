@@ -0,0 +1,195 @@
+use crate::visualizer::{Area, Visualizer};
+
+// All games open in the window. With more than one game (comparing two runs) the window
+// is split in equal columns and the games are stepped in lockstep by turn number.
+pub struct Session {
+    pub games: Vec<(String, Visualizer)>,
+    pub turn: usize,
+    pub show_diff: bool,
+}
+
+impl Session {
+    pub fn new(games: Vec<(String, Visualizer)>, window_size: (u32, u32)) -> Self {
+        let mut session = Session { games, turn: 0, show_diff: false };
+        session.resize(window_size.0, window_size.1);
+        session
+    }
+
+    pub fn resize(&mut self, width: u32, height: u32) {
+        let count = self.games.len().max(1) as u32;
+        for (i, (_, game)) in self.games.iter_mut().enumerate() {
+            let x = (i as u32 * width / count) as i32;
+            game.set_viewport(Area::new(x, 0, width / count, height));
+        }
+    }
+
+    fn last_turn_index(&self) -> usize {
+        self.games.iter().map(|(_, game)| game.fields.len() - 1).max().unwrap_or(0)
+    }
+
+    // Games that ended earlier stay on their last turn
+    pub fn set_turn(&mut self, turn: usize) {
+        self.turn = turn.min(self.last_turn_index());
+        for (_, game) in &mut self.games {
+            game.set_turn(self.turn);
+        }
+    }
+
+    pub fn next_turn(&mut self) {
+        self.set_turn(self.turn + 1);
+    }
+
+    pub fn prev_turn(&mut self) {
+        self.set_turn(self.turn.saturating_sub(1));
+    }
+
+    pub fn first_turn(&mut self) {
+        self.set_turn(0);
+    }
+
+    pub fn last_turn(&mut self) {
+        self.set_turn(self.last_turn_index());
+    }
+
+    // Territory of the second game minus the one of the first on the current turn, for player 1
+    // and player 2, when comparing runs
+    pub fn territory_delta(&self) -> Option<(i64, i64)> {
+        let [(_, a), (_, b), ..] = &self.games[..] else {
+            return None;
+        };
+        let ((a1, a2), (b1, b2)) = (a.territory(), b.territory());
+        Some((b1 as i64 - a1 as i64, b2 as i64 - a2 as i64))
+    }
+
+    pub fn toggle_heatmap(&mut self) {
+        for (_, game) in &mut self.games {
+            game.toggle_heatmap();
+        }
+    }
+
+    pub fn toggle_diff(&mut self) {
+        self.show_diff = !self.show_diff;
+    }
+
+    pub fn fit_to_window(&mut self) {
+        for (_, game) in &mut self.games {
+            game.fit_to_window();
+        }
+    }
+
+    // Game that receives the mouse: the one being dragged, otherwise the one under the cursor
+    fn active_game(&self, x: i32, y: i32) -> Option<usize> {
+        self.games
+            .iter()
+            .position(|(_, game)| game.dragging_timeline || game.panning_from.is_some())
+            .or_else(|| self.games.iter().position(|(_, game)| game.viewport.contains_point((x, y))))
+    }
+
+    // Copies turn, zoom, pan and hovered cell of the active game to the others
+    fn sync_from(&mut self, active: usize) {
+        let (_, source) = &self.games[active];
+        let (turn, zoom, pan, hovered_cell) = (source.turn, source.zoom, source.pan, source.hovered_cell);
+        self.set_turn(turn);
+        for (_, game) in &mut self.games {
+            game.zoom = zoom;
+            game.pan = pan;
+            game.hovered_cell = hovered_cell;
+        }
+    }
+
+    pub fn mouse_down(&mut self, x: i32, y: i32) {
+        if let Some(active) = self.active_game(x, y) {
+            self.games[active].1.mouse_down(x, y);
+            self.sync_from(active);
+        }
+    }
+
+    pub fn mouse_move(&mut self, x: i32, y: i32) {
+        if let Some(active) = self.active_game(x, y) {
+            self.games[active].1.mouse_move(x, y);
+            self.sync_from(active);
+        }
+    }
+
+    pub fn mouse_up(&mut self) {
+        for (_, game) in &mut self.games {
+            game.mouse_up();
+        }
+    }
+
+    pub fn zoom_at_mouse(&mut self, x: i32, y: i32, steps: i32) {
+        if let Some(active) = self.active_game(x, y) {
+            self.games[active].1.zoom_at_mouse(steps);
+            self.sync_from(active);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME_LOG: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../logs/game_log.txt"));
+
+    fn load(text: &str) -> Visualizer {
+        Visualizer::new(text.lines().map(|line| Ok(line.to_string())))
+    }
+
+    // The game log cut before its board of the given turn
+    fn first_turns(turns: usize) -> Visualizer {
+        let cut = GAME_LOG.match_indices("Anfield").nth(turns).unwrap().0;
+        load(&GAME_LOG[..cut])
+    }
+
+    fn session(games: Vec<Visualizer>) -> Session {
+        let games = games.into_iter().enumerate().map(|(i, game)| (format!("run{}", i + 1), game)).collect();
+        Session::new(games, (1200, 800))
+    }
+
+    fn turns(session: &Session) -> Vec<usize> {
+        session.games.iter().map(|(_, game)| game.turn).collect()
+    }
+
+    #[test]
+    fn test_games_step_in_lockstep() {
+        let mut session = session(vec![load(GAME_LOG), load(GAME_LOG)]);
+        session.next_turn();
+        session.next_turn();
+        assert_eq!((session.turn, turns(&session)), (2, vec![2, 2]));
+        session.prev_turn();
+        assert_eq!((session.turn, turns(&session)), (1, vec![1, 1]));
+        session.first_turn();
+        session.prev_turn();
+        assert_eq!((session.turn, turns(&session)), (0, vec![0, 0]));
+    }
+
+    #[test]
+    fn test_shorter_game_stays_on_its_last_turn() {
+        let full = load(GAME_LOG);
+        let last = full.fields.len() - 1;
+        let mut session = session(vec![first_turns(5), full]);
+
+        session.set_turn(3);
+        assert_eq!(turns(&session), vec![3, 3]);
+        session.last_turn();
+        assert_eq!((session.turn, turns(&session)), (last, vec![4, last]));
+        // Past the end of the longest game
+        session.next_turn();
+        assert_eq!(session.turn, last);
+        session.set_turn(last + 10);
+        assert_eq!(session.turn, last);
+    }
+
+    #[test]
+    fn test_territory_delta() {
+        assert_eq!(session(vec![load(GAME_LOG)]).territory_delta(), None);
+
+        let mut session = session(vec![first_turns(5), load(GAME_LOG)]);
+        assert_eq!(session.territory_delta(), Some((0, 0)));
+        session.last_turn();
+        let (short1, short2) = session.games[0].1.territory();
+        let (full1, full2) = session.games[1].1.territory();
+        assert_eq!(session.territory_delta(), Some((full1 as i64 - short1 as i64, full2 as i64 - short2 as i64)));
+        assert!(full1 > short1 && full2 > short2);
+    }
+}
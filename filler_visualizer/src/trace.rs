@@ -0,0 +1,56 @@
+use std::fs;
+
+use serde::Deserialize;
+
+// Decision trace written by my_robot when FILLER_TRACE is set, one JSON object per line.
+// The first line is the header, every following line is one turn of the robot.
+#[derive(Debug, Clone, Deserialize)]
+struct TraceHeader {
+    player: usize,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Candidate {
+    pub x: i32,
+    pub y: i32,
+    pub score: i32,
+    pub breakdown: Vec<(String, i32)>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct TraceTurn {
+    pub turn: usize,
+    pub answer: (i32, i32),
+    pub candidates: Vec<Candidate>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub player: usize,
+    pub turns: Vec<TraceTurn>,
+}
+
+impl Trace {
+    // Lines that can't be parsed (e.g. a turn cut off by a crash) are skipped
+    pub fn load(filename: &str) -> Result<Self, String> {
+        let content = fs::read_to_string(filename)
+            .map_err(|err| format!("Failed to read trace {}: {}", filename, err))?;
+        let mut lines = content.lines();
+
+        let header: TraceHeader = lines
+            .next()
+            .and_then(|line| serde_json::from_str(line).ok())
+            .ok_or_else(|| format!("Invalid trace header in {}", filename))?;
+
+        let turns = lines
+            .filter_map(|line| serde_json::from_str(line).ok())
+            .collect();
+
+        Ok(Trace { player: header.player, turns })
+    }
+
+    // The robot counts its own turns starting from 1
+    pub fn get(&self, robot_turn: usize) -> Option<&TraceTurn> {
+        self.turns.iter().find(|turn| turn.turn == robot_turn)
+    }
+}
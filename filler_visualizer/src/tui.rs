@@ -0,0 +1,156 @@
+use std::io::{self, Stdout, Write};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use crossterm::style::{Color, Print, ResetColor, SetBackgroundColor, SetForegroundColor};
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use crate::session::Session;
+use crate::visualizer::*;
+use crate::{load_session, Options};
+
+// Columns kept free on the right of the board for the side panel
+const PANEL_WIDTH: u16 = 44;
+
+// Draws the games in the terminal until `q` or `Esc` is pressed. When comparing two
+// logs only one game is shown at a time and `Tab` switches between them.
+pub fn run(options: &Options) -> io::Result<()> {
+    let mut stdout = io::stdout();
+    let mut session = load_session(options, (0, 0));
+
+    terminal::enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+
+    let result = event_loop(&mut stdout, options, &mut session);
+
+    execute!(stdout, ResetColor, Show, LeaveAlternateScreen)?;
+    terminal::disable_raw_mode()?;
+    result
+}
+
+fn event_loop(stdout: &mut Stdout, options: &Options, session: &mut Session) -> io::Result<()> {
+    let mut shown = 0;
+    loop {
+        draw(stdout, session, shown)?;
+
+        match event::read()? {
+            Event::Key(key) if key.kind == KeyEventKind::Press => match key.code {
+                KeyCode::Esc | KeyCode::Char('q') => return Ok(()),
+                KeyCode::Right => session.next_turn(),
+                KeyCode::Left => session.prev_turn(),
+                KeyCode::Up => session.first_turn(),
+                KeyCode::Down => session.last_turn(),
+                KeyCode::Tab => shown = (shown + 1) % session.games.len(),
+                KeyCode::Char('r') => *session = load_session(options, (0, 0)),
+                _ => {}
+            },
+            _ => {}
+        }
+    }
+}
+
+fn term_color((r, g, b): (u8, u8, u8)) -> Color {
+    Color::Rgb { r, g, b }
+}
+
+fn draw(stdout: &mut Stdout, session: &Session, shown: usize) -> io::Result<()> {
+    let (cols, rows) = terminal::size()?;
+    let (name, game) = &session.games[shown];
+    let field = &game.fields[game.turn];
+
+    queue!(stdout, ResetColor, Clear(ClearType::All))?;
+
+    // Cells are two characters wide when the board fits, otherwise each character holds
+    // two rows of one column with the upper half block
+    let board_cols = cols.saturating_sub(PANEL_WIDTH) as usize;
+    let full_blocks = field.size.width * 2 <= board_cols && field.size.height < rows as usize;
+    let board_width = if full_blocks {
+        for (row_idx, row) in field.cells.iter().enumerate().take(rows as usize) {
+            queue!(stdout, MoveTo(0, row_idx as u16))?;
+            for &ch in row {
+                queue!(stdout, SetForegroundColor(term_color(cell_color(ch))), Print("██"))?;
+            }
+        }
+        field.size.width * 2
+    } else {
+        let width = field.size.width.min(board_cols);
+        for (line, pair) in field.cells.chunks(2).enumerate().take(rows as usize) {
+            queue!(stdout, MoveTo(0, line as u16))?;
+            for col in 0..width {
+                let bottom = match pair.get(1) {
+                    Some(row) => term_color(cell_color(row[col])),
+                    None => Color::Reset,
+                };
+                queue!(
+                    stdout,
+                    SetForegroundColor(term_color(cell_color(pair[0][col]))),
+                    SetBackgroundColor(bottom),
+                    Print("▀")
+                )?;
+            }
+            queue!(stdout, ResetColor)?;
+        }
+        width
+    };
+
+    // Side panel
+    let x = board_width as u16 + 2;
+    let text_width = cols.saturating_sub(x) as usize;
+    let white = Color::White;
+    let mut y = 0;
+    let mut line = |stdout: &mut Stdout, text: &str, color: Color| -> io::Result<()> {
+        let text: String = text.chars().take(text_width).collect();
+        queue!(stdout, MoveTo(x, y), SetForegroundColor(color), Print(text))?;
+        y += 1;
+        Ok(())
+    };
+
+    if session.games.len() > 1 {
+        line(stdout, &format!("[{}/{}] {}", shown + 1, session.games.len(), name), white)?;
+        let (a1, a2) = session.games[0].1.territory();
+        let (b1, b2) = session.games[1].1.territory();
+        line(stdout, &format!("Delta: Player 1 {:+}  Player 2 {:+}",
+            b1 as i64 - a1 as i64, b2 as i64 - a2 as i64), white)?;
+        line(stdout, "", white)?;
+    }
+
+    let (p1_score, p2_score) = game.territory();
+    let (p1_col, p2_col) = (term_color(P1_COLOR), term_color(P2_COLOR));
+    line(stdout, &format!("Turn: {} / {}", game.turn + 1, game.fields.len()), white)?;
+    line(stdout, "", white)?;
+    line(stdout, &format!("Player 1: {}", game.players[0].path), p1_col)?;
+    line(stdout, &format!("   score: {}", p1_score), p1_col)?;
+    line(stdout, &format!("Player 2: {}", game.players[1].path), p2_col)?;
+    line(stdout, &format!("   score: {}", p2_score), p2_col)?;
+    line(stdout, "", white)?;
+
+    if let Some((player, piece)) = game.pieces.get(game.turn) {
+        let col = match player {
+            1 => p1_col,
+            2 => p2_col,
+            _ => white,
+        };
+        line(stdout, &format!("Player {} placing:", player), col)?;
+        for row in &piece.cells {
+            let row: String = row.iter().map(|&c| if c == 'O' { "██" } else { "··" }).collect();
+            line(stdout, &row, col)?;
+        }
+        line(stdout, "", white)?;
+    }
+
+    for (_, event) in game.events.iter().filter(|(turn, _)| *turn == game.turn) {
+        line(stdout, event, Color::Red)?;
+    }
+
+    line(stdout, "", white)?;
+    let help = if session.games.len() > 1 {
+        "←/→ step  ↑/↓ first/last  Tab switch  r reload  q quit"
+    } else {
+        "←/→ step  ↑/↓ first/last  r reload  q quit"
+    };
+    line(stdout, help, Color::DarkGrey)?;
+
+    queue!(stdout, ResetColor)?;
+    stdout.flush()
+}
@@ -1,228 +1,416 @@
 pub use crate::field::*;
 pub use crate::piece::*;
 pub use crate::player::*;
+pub use crate::trace::*;
+use crate::grid::Size;
+use filler_log::{GameRecord, ParsedLog};
+
+// The board area fills the window except for the margins, the side panel on the right
+// and the timeline that sits in the bottom margin
+const MARGIN: i32 = 50;
+const PANEL_WIDTH: i32 = 320;
+const PANEL_GAP: i32 = 30;
+const TIMELINE_GAP: i32 = 15;
+const TIMELINE_HEIGHT: u32 = 14;
+const MIN_ZOOM: f32 = 0.5;
+const MAX_ZOOM: f32 = 40.0;
+const ZOOM_STEP: f32 = 1.2;
+
+// Colors of the board cells, shared by every renderer
+pub const P1_COLOR: (u8, u8, u8) = (255, 100, 100);
+pub const P2_COLOR: (u8, u8, u8) = (100, 100, 255);
+
+pub fn cell_color(ch: char) -> (u8, u8, u8) {
+    match ch {
+        '@' => (150, 60, 60),
+        'a' => P1_COLOR,
+        '$' => (60, 60, 150),
+        's' => P2_COLOR,
+        '.' => (40, 40, 40),
+        _ => (80, 80, 80),
+    }
+}
+
+// Rectangle in window pixels, kept independent from the renderer
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Area {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl Area {
+    pub fn new(x: i32, y: i32, width: u32, height: u32) -> Self {
+        Area { x, y, width, height }
+    }
+
+    pub fn right(&self) -> i32 {
+        self.x + self.width as i32
+    }
 
-use sdl2::pixels::Color;
-use sdl2::rect::Rect;
-use sdl2::render::Canvas;
-use sdl2::video::Window;
-use sdl2::render::TextureCreator;
-use sdl2::ttf::Font;
-use sdl2::video::WindowContext;
+    pub fn bottom(&self) -> i32 {
+        self.y + self.height as i32
+    }
+
+    pub fn contains_point(&self, (x, y): (i32, i32)) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+}
+
+// Where things are drawn for the current window size, zoom and pan
+pub struct Layout {
+    pub board_area: Area,
+    pub origin: (f32, f32),
+    pub cell_size: f32,
+    pub timeline: Area,
+    pub panel_x: i32,
+}
+
+impl Layout {
+    // Cell edges are rounded separately so that neighbouring cells never overlap or leave gaps
+    pub fn cell_rect(&self, col: usize, row: usize) -> Area {
+        let x0 = (self.origin.0 + col as f32 * self.cell_size).round() as i32;
+        let y0 = (self.origin.1 + row as f32 * self.cell_size).round() as i32;
+        let x1 = (self.origin.0 + (col + 1) as f32 * self.cell_size).round() as i32;
+        let y1 = (self.origin.1 + (row + 1) as f32 * self.cell_size).round() as i32;
+        Area::new(x0, y0, (x1 - x0).max(1) as u32, (y1 - y0).max(1) as u32)
+    }
+}
 
 pub struct Visualizer {
     pub players: [Player; 2],
     pub fields: Vec<Field>,
     pub pieces: Vec<(usize, Piece)>,
+    pub events: Vec<(usize, String)>,
     pub turn: usize,
+    pub hovered_cell: Option<(usize, usize)>,
+    pub dragging_timeline: bool,
+    pub mouse_pos: (i32, i32),
+    pub trace: Option<Trace>,
+    pub show_heatmap: bool,
+    pub viewport: Area,
+    pub zoom: f32,
+    pub pan: (f32, f32),
+    pub panning_from: Option<(i32, i32)>,
 }
 
 impl Visualizer {
-    pub fn new<I>(mut lines: I) -> Self
+    // Loads as much of the log as possible: unknown lines between the blocks (bot stderr,
+    // engine noise) are skipped, loading stops at the first truncated block or read error
+    // and engine errors are kept as events on the turn they happened
+    pub fn new<I>(lines: I) -> Self
     where
         I: Iterator<Item = Result<String, std::io::Error>>,
     {
-        let player1_line = lines
-            .next()
-            .expect("Missing line for player 1")
-            .expect("Failed to read player 1 line");
-
-        let player2_line = lines
-            .next()
-            .expect("Missing line for player 2")
-            .expect("Failed to read player 2 line");
-
-        let player1 = Player::new(&player1_line);
-        let player2 = Player::new(&player2_line);
-
-        let mut fields = Vec::new();
-        let mut pieces = Vec::new();
-
-        while let Some(Ok(line)) = lines.next() {
-            if line.starts_with("Anfield") {
-                let mut field = Field::new(&line);
-                field.update(&mut lines);
-                fields.push(field);
-
-            } else if line.starts_with("Piece") {
-                let mut piece = Piece::new(&line);
-                piece.update(&mut lines);
-
-                let answer_line = lines.next().unwrap().unwrap();
-                let symbol = answer_line.chars().nth(11).unwrap_or(' ');
-
-                let player = match symbol {
-                    '@' => 1,
-                    '$' => 2,
-                    _ => 0,
-                };
-
-                pieces.push((player, piece));
+        let mut read_error = None;
+        let lines = lines.map_while(|line| line.map_err(|err| read_error = Some(err)).ok());
+        let ParsedLog { record, truncated } = GameRecord::from_text_lossy(lines);
+        // A read error is why the last block is cut, if there is one
+        let truncated = read_error.map(|err| format!("read error: {}", err)).or(truncated);
+
+        let players = [Player::new(1, &record.players[0]), Player::new(2, &record.players[1])];
+        let mut fields: Vec<Field> = record.turns.iter().map(|turn| Field::from(&turn.board)).collect();
+        let pieces: Vec<(usize, Piece)> = record.turns.iter()
+            .map(|turn| (turn.player().unwrap_or(0), Piece::from(&turn.piece)))
+            .collect();
+        let answers: Vec<Option<String>> = record.turns.iter()
+            .map(|turn| turn.answer.map(|answer| format!("{} {}", answer.x, answer.y)))
+            .collect();
+
+        // An error logged after n turns belongs to the last board shown at that point
+        let mut events: Vec<(usize, String)> = record.errors.into_iter()
+            .map(|error| (error.turn.saturating_sub(1), error.message))
+            .collect();
+        if let Some(err) = truncated {
+            events.push((fields.len().saturating_sub(1), format!("Log truncated: {}", err)));
+        }
+
+        // A move that did not add any cell for its player was rejected by the engine
+        for (turn, (player, _)) in pieces.iter().enumerate() {
+            let (Some(before), Some(after)) = (fields.get(turn), fields.get(turn + 1)) else {
+                break;
+            };
+            if *player == 0 {
+                continue;
             }
+            let owner = &players[player - 1];
+            let placed = before.cells.iter().flatten()
+                .zip(after.cells.iter().flatten())
+                .any(|(b, a)| *b == '.' && owner.is_mine(a));
+            if !placed {
+                let answer = answers[turn].as_deref().unwrap_or("-");
+                events.push((turn, format!("Player {}: invalid move {}", player, answer)));
+            }
+        }
+        events.sort_by_key(|(turn, _)| *turn);
+
+        // Keep at least one (empty) field so a log without any board can still be shown
+        if fields.is_empty() {
+            fields.push(Field { size: Size { width: 0, height: 0 }, cells: vec![] });
         }
 
         Visualizer {
-            players: [player1, player2],
+            players,
             fields,
             pieces,
+            events,
             turn: 0,
+            hovered_cell: None,
+            dragging_timeline: false,
+            mouse_pos: (0, 0),
+            trace: None,
+            show_heatmap: true,
+            viewport: Area::new(0, 0, 1200, 900),
+            zoom: 1.0,
+            pan: (0.0, 0.0),
+            panning_from: None,
         }
     }
 
-    pub fn draw(
-        &self,
-        canvas: &mut Canvas<Window>,
-        font: &Font,
-        texture_creator: &TextureCreator<WindowContext>,
-    ) {
+    pub fn layout(&self) -> Layout {
         let field = &self.fields[self.turn];
-        let max_grid_size = 800;
-
-        let cell_size = (max_grid_size / field.size.width.max(1))
-                                .min(max_grid_size / field.size.height.max(1));
-        let margin = 1;
-
-        let mut p1_score = 0;
-        let mut p2_score = 0;
-        let p1_col = Color::RGB(255, 100, 100);
-        let p2_col = Color::RGB(100, 100, 255);
-
-        // Draw field grid at (50, 50)
-        for (row_idx, row) in field.cells.iter().enumerate() {
-            for (col_idx, &ch) in row.iter().enumerate() {
-                let x = 50 + col_idx as u32 * cell_size as u32;
-                let y = 50 + row_idx as u32 * cell_size as u32;
-                
-                match ch {
-                    '@' | 'a' => p1_score += 1,
-                    '$' | 's' => p2_score += 1,
-                    _ => {}
-                }
-
-                let rect = Rect::new(
-                    x as i32 + margin,
-                    y as i32 + margin,
-                    (cell_size as u32).saturating_sub(2 * margin as u32),
-                    (cell_size as u32).saturating_sub(2 * margin as u32),
-                );
-                let color = match ch {
-                    '@' => Color::RGB(150, 60, 60),
-                    'a' => Color::RGB(255, 100, 100),
-                    '$' => Color::RGB(60, 60, 150),
-                    's' => Color::RGB(100, 100, 255),
-                    '.' => Color::RGB(40, 40, 40),
-                    _ => Color::RGB(80, 80, 80),
-                };
-
-                canvas.set_draw_color(color);
-                canvas.fill_rect(rect).ok();
-            }
+        let viewport = self.viewport;
+
+        let panel_x = (viewport.right() - PANEL_WIDTH).max(viewport.x + MARGIN + PANEL_GAP + 1);
+        let board_area = Area::new(
+            viewport.x + MARGIN,
+            viewport.y + MARGIN,
+            (panel_x - PANEL_GAP - viewport.x - MARGIN).max(1) as u32,
+            (viewport.height as i32 - 2 * MARGIN).max(1) as u32,
+        );
+
+        // At zoom 1 the whole field fits in the board area
+        let fit = (board_area.width as usize / field.size.width.max(1))
+            .min(board_area.height as usize / field.size.height.max(1))
+            .max(1) as f32;
+
+        Layout {
+            origin: (board_area.x as f32 + self.pan.0, board_area.y as f32 + self.pan.1),
+            cell_size: fit * self.zoom,
+            timeline: Area::new(
+                board_area.x,
+                board_area.bottom() + TIMELINE_GAP,
+                board_area.width,
+                TIMELINE_HEIGHT,
+            ),
+            board_area,
+            panel_x,
         }
+    }
 
-        // Draw text: Player info & Turn
-        draw_text(canvas, texture_creator, font,
-            &format!("Turn: {} / {}", self.turn + 1, self.fields.len()),
-            880, 50, Color { r: 255, g: 255, b: 255, a: 0 });
-
-        draw_text(canvas, texture_creator, font,
-            &format!("Player 1: {}", self.players[0].path),
-            880, 110, p1_col);
-        draw_text(canvas, texture_creator, font,
-            &format!("   score: {}", p1_score),
-            880, 140, p1_col);
-
-        draw_text(canvas, texture_creator, font,
-            &format!("Player 2: {}", self.players[1].path),
-            880, 170, p2_col);
-        draw_text(canvas, texture_creator, font,
-            &format!("   score: {}", p2_score),
-            880, 200, p2_col);
-
-
-        // Draw piece grid (100x100 max size)
-        if let Some((player, piece)) = self.pieces.get(self.turn) {
-            let px = 880;
-            let py = 250;
-
-            let col = match player {
-                1 => p1_col,
-                2 => p2_col,
-                _ => Color { r: 255, g: 255, b: 255, a: 0 },
-            };
+    // Returns the (x, y) board cell under the given window position, if any
+    pub fn cell_at(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        let field = &self.fields[self.turn];
+        let layout = self.layout();
+        if !layout.board_area.contains_point((x, y)) {
+            return None;
+        }
+        let col = ((x as f32 - layout.origin.0) / layout.cell_size).floor();
+        let row = ((y as f32 - layout.origin.1) / layout.cell_size).floor();
+        if col < 0.0 || row < 0.0 || col as usize >= field.size.width || row as usize >= field.size.height {
+            return None;
+        }
+        Some((col as usize, row as usize))
+    }
 
-            draw_text(canvas, texture_creator, font,
-                &format!("Player {} placing: ", player),
-                880, py, col);
+    // Part of the window this game is drawn in
+    pub fn set_viewport(&mut self, viewport: Area) {
+        self.viewport = viewport;
+    }
 
-            let cell_size = (150 / piece.size.width.max(1))
-                                .min(150 / piece.size.height.max(1)).min(50);
-            let margin = 1;
+    // Number of cells owned by player 1 and player 2 on the current turn
+    pub fn territory(&self) -> (usize, usize) {
+        self.territory_at(self.turn)
+    }
 
-            for (row_idx, row) in piece.cells.iter().enumerate() {
-                for (col_idx, &ch) in row.iter().enumerate() {
+    pub fn territory_at(&self, turn: usize) -> (usize, usize) {
+        let cells = self.fields[turn].cells.iter().flatten();
+        cells.fold((0, 0), |(p1, p2), ch| match self.owner(*ch) {
+            1 => (p1 + 1, p2),
+            2 => (p1, p2 + 1),
+            _ => (p1, p2),
+        })
+    }
+
+    // Player number owning the cell, 0 when empty
+    pub fn owner(&self, ch: char) -> usize {
+        if self.players[0].is_mine(&ch) {
+            1
+        } else if self.players[1].is_mine(&ch) {
+            2
+        } else {
+            0
+        }
+    }
 
-                    let x = px + col_idx as i32 * cell_size as i32;
-                    let y = py + 30 + row_idx as i32 * cell_size as i32;
+    // Zooms in (positive steps) or out around the mouse cursor
+    pub fn zoom_at_mouse(&mut self, steps: i32) {
+        let layout = self.layout();
+        let zoom = (self.zoom * ZOOM_STEP.powi(steps)).clamp(MIN_ZOOM, MAX_ZOOM);
+        let factor = zoom / self.zoom;
+        let (x, y) = (self.mouse_pos.0 as f32, self.mouse_pos.1 as f32);
+
+        // Keep the board point under the cursor in place
+        let origin = (x - (x - layout.origin.0) * factor, y - (y - layout.origin.1) * factor);
+        self.pan = (
+            origin.0 - layout.board_area.x as f32,
+            origin.1 - layout.board_area.y as f32,
+        );
+        self.zoom = zoom;
+    }
 
-                    let rect = Rect::new(
-                        x + margin,
-                        y + margin,
-                        (cell_size as u32).saturating_sub(2 * margin as u32),
-                        (cell_size as u32).saturating_sub(2 * margin as u32),
-                    );
+    pub fn fit_to_window(&mut self) {
+        self.zoom = 1.0;
+        self.pan = (0.0, 0.0);
+    }
 
-                    let color = match ch {
-                        'O' => col,
-                        _   => Color::RGB(50, 50, 50),
-                    };
+    // Maps a horizontal window position on the timeline to a turn index
+    fn turn_at(&self, x: i32) -> usize {
+        let rect = self.layout().timeline;
+        let last = self.fields.len().saturating_sub(1);
+        let rel = (x - rect.x).clamp(0, rect.width as i32) as usize;
+        (rel * last + rect.width as usize / 2) / rect.width as usize
+    }
 
-                    canvas.set_draw_color(color);
-                    canvas.fill_rect(rect).ok();
-                }
-            }
+    // Index of the turn on which the cell was first seen occupied, None if it is still empty
+    // on the last turn. Index 0 means the cell was a starting position.
+    pub fn claimed_turn(&self, x: usize, y: usize) -> Option<usize> {
+        self.fields
+            .iter()
+            .position(|field| field.cells.get(y).and_then(|row| row.get(x)).is_some_and(|&cell| cell != '.'))
+    }
+
+    // Trace of the robot's decision for the current turn, if it was the traced robot's move
+    pub fn trace_turn(&self) -> Option<&TraceTurn> {
+        let trace = self.trace.as_ref()?;
+        let (player, _) = self.pieces.get(self.turn)?;
+        if *player != trace.player {
+            return None;
         }
+        let robot_turn = self.pieces[..=self.turn]
+            .iter()
+            .filter(|(p, _)| *p == trace.player)
+            .count();
+        trace.get(robot_turn)
     }
-    
-    pub fn next_turn(&mut self) {
-        if self.turn + 1 < self.fields.len() {
-            self.turn += 1;
+
+    // Top left cell of the trimmed piece for an answer, candidates are drawn there since
+    // the answer itself can be off the board when the piece has empty rows or columns
+    pub fn candidate_cell(&self, x: i32, y: i32) -> Option<(usize, usize)> {
+        let field = &self.fields[self.turn];
+        let (_, piece) = self.pieces.get(self.turn)?;
+        let top = piece.cells.iter().position(|row| row.contains(&'O')).unwrap_or(0) as i32;
+        let left = (0..piece.size.width)
+            .find(|&col| piece.cells.iter().any(|row| row[col] == 'O'))
+            .unwrap_or(0) as i32;
+        let (col, row) = (x + left, y + top);
+        if col < 0 || row < 0 || col as usize >= field.size.width || row as usize >= field.size.height {
+            return None;
         }
+        Some((col as usize, row as usize))
     }
 
-    pub fn prev_turn(&mut self) {
-        if self.turn > 0 {
-            self.turn -= 1;
+    pub fn toggle_heatmap(&mut self) {
+        self.show_heatmap = !self.show_heatmap;
+    }
+
+    pub fn mouse_down(&mut self, x: i32, y: i32) {
+        let layout = self.layout();
+        if layout.timeline.contains_point((x, y)) {
+            self.dragging_timeline = true;
+            self.turn = self.turn_at(x);
+        } else if layout.board_area.contains_point((x, y)) {
+            self.panning_from = Some((x, y));
         }
     }
 
-    pub fn first_turn(&mut self) {
-        self.turn = 0;
+    pub fn mouse_move(&mut self, x: i32, y: i32) {
+        self.mouse_pos = (x, y);
+        if self.dragging_timeline {
+            self.turn = self.turn_at(x);
+        }
+        if let Some((from_x, from_y)) = self.panning_from {
+            self.pan.0 += (x - from_x) as f32;
+            self.pan.1 += (y - from_y) as f32;
+            self.panning_from = Some((x, y));
+        }
+        self.hovered_cell = self.cell_at(x, y);
     }
 
-    pub fn last_turn(&mut self) {
-        self.turn = self.fields.len() -1;
+    pub fn mouse_up(&mut self) {
+        self.dragging_timeline = false;
+        self.panning_from = None;
+    }
+
+    pub fn set_turn(&mut self, turn: usize) {
+        self.turn = turn.min(self.fields.len() - 1);
     }
 
 }
 
-fn draw_text(
-    canvas: &mut Canvas<Window>,
-    texture_creator: &TextureCreator<WindowContext>,
-    font: &Font,
-    text: &str,
-    x: i32,
-    y: i32,
-    color: Color,
-) {
-    let surface = font
-        .render(text)
-        .blended(color)
-        .unwrap();
-
-    let texture = texture_creator
-        .create_texture_from_surface(&surface)
-        .unwrap();
-
-    let target = Rect::new(x, y, surface.width(), surface.height());
-    canvas.copy(&texture, None, Some(target)).unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    const GAME_LOG: &str = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/../logs/game_log.txt"));
+
+    fn load(text: &str) -> Visualizer {
+        Visualizer::new(text.lines().map(|line| Ok(line.to_string())))
+    }
+
+    fn truncation(visualizer: &Visualizer) -> Option<&(usize, String)> {
+        visualizer.events.iter().find(|(_, event)| event.starts_with("Log truncated"))
+    }
+
+    #[test]
+    fn test_noise_between_blocks_is_skipped() {
+        let noisy = GAME_LOG
+            .replacen("Anfield", "bot debug\nAnfield", 1)
+            .replace("-> Answer (@): 7 1\n", "-> Answer (@): 7 1\nplaced at 7 1\n");
+        let visualizer = load(&noisy);
+        assert_eq!(visualizer.fields.len(), load(GAME_LOG).fields.len());
+        assert!(truncation(&visualizer).is_none());
+    }
+
+    #[test]
+    fn test_truncated_board_is_not_spliced() {
+        // The third board loses its last rows, the next block follows right away
+        let third = GAME_LOG.match_indices("Anfield").nth(2).unwrap().0;
+        let cut = third + GAME_LOG[third..].find("010 ").unwrap();
+        let next = third + GAME_LOG[third..].find("Piece").unwrap();
+        let next = next + GAME_LOG[next..].find("Anfield").unwrap();
+        let visualizer = load(&format!("{}{}", &GAME_LOG[..cut], &GAME_LOG[next..]));
+
+        // The two whole boards are shown, the truncation is an event on the last one
+        assert_eq!(visualizer.fields.len(), 2);
+        assert_eq!(truncation(&visualizer).map(|(turn, _)| *turn), Some(1));
+    }
+
+    #[test]
+    fn test_claimed_turn_skips_smaller_boards() {
+        let mut visualizer = load(GAME_LOG);
+        let (first, last) = (&visualizer.fields[0], visualizer.fields.last().unwrap());
+        let (y, x) = (0..first.cells.len())
+            .flat_map(|y| (0..first.cells[y].len()).map(move |x| (y, x)))
+            .find(|&(y, x)| first.cells[y][x] == '.' && last.cells[y][x] != '.')
+            .unwrap();
+        let claimed = visualizer.claimed_turn(x, y);
+
+        // The first board ends above the cell
+        visualizer.fields[0].cells.truncate(y);
+        assert_eq!(visualizer.claimed_turn(x, y), claimed);
+    }
+
+    #[test]
+    fn test_loading_stops_at_a_read_error() {
+        let lines = GAME_LOG.lines()
+            .take(30)
+            .map(|line| Ok(line.to_string()))
+            .chain(std::iter::repeat_with(|| Err(io::Error::other("disk gone"))));
+        let visualizer = Visualizer::new(lines);
+        assert_eq!(visualizer.fields.len(), 1);
+        assert!(visualizer.events.iter().any(|(_, event)| event.contains("disk gone")));
+    }
 }
@@ -0,0 +1,5 @@
+use std::io;
+
+fn main() {
+    baseline_bots::play(io::stdin().lock(), &mut io::stdout().lock(), baseline_bots::first_fit);
+}
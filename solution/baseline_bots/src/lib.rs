@@ -0,0 +1,210 @@
+use std::collections::VecDeque;
+use std::env;
+use std::io::{BufRead, Write};
+
+use my_robot::game::*;
+use my_robot::reader::Reader;
+use my_robot::rng::Rng;
+
+// Simple bots on the protocol, board and placement search of my_robot: opponents whose play is
+// easy to follow, and a floor to measure the heuristics of the robot against.
+
+// Seed of `random_legal`, the same seed plays the same game against the same opponent
+pub const SEED_ENV: &str = "FILLER_SEED";
+const DEFAULT_SEED: u64 = 1;
+
+// Plays a game from the engine input, answering each piece with the placement `choose` picks
+// among the valid ones. They are given in the order of the search, row by row from the top
+// left, and never empty: the answer is `0 0` when the piece fits nowhere.
+pub fn play<R: BufRead, W: Write>(input: R, output: &mut W, mut choose: impl FnMut(&Game, &[Placement]) -> usize) {
+    let mut reader = Reader::new(input);
+    let (player, _) = Player::new(reader.next_line().expect("Missing player line"));
+    let header = reader.next_line().expect("Missing board header");
+    let dialect = Dialect::detect(header).expect("Unknown board header");
+    let field = Field::new(header, dialect, player.symbol);
+    reader.dialect = dialect;
+    let mut game = Game::new(player, field);
+    game.update_field(&mut reader);
+
+    let mut piece = Piece::default();
+    while let Some(line) = reader.next_line() {
+        if line.starts_with(dialect.board_keyword()) {
+            game.update_field(&mut reader);
+        } else if line.starts_with("Piece") {
+            piece.reset(line, dialect);
+            piece.update(&mut reader);
+
+            let placements = game.valid_placements(&piece);
+            let (x, y) = match placements.is_empty() {
+                true => (0, 0),
+                false => placements[choose(&game, &placements)].answer(),
+            };
+            writeln!(output, "{}", dialect.answer(x, y)).expect("Failed to write the answer");
+            output.flush().expect("Failed to write the answer");
+        }
+    }
+}
+
+// The first placement found, the one nearest the top left of the board
+pub fn first_fit(_: &Game, _: &[Placement]) -> usize {
+    0
+}
+
+// Any placement, from the seed of `FILLER_SEED` or 1
+pub fn random_legal() -> impl FnMut(&Game, &[Placement]) -> usize {
+    let seed = env::var(SEED_ENV).ok().and_then(|seed| seed.parse().ok()).unwrap_or(DEFAULT_SEED);
+    let mut rng = Rng::new(seed);
+    move |_, placements| rng.below(placements.len())
+}
+
+// The placement that leaves the most empty cells closer to us than to the enemy
+pub fn max_area(game: &Game, placements: &[Placement]) -> usize {
+    let mut area = ClaimedArea::new(game);
+    best(placements, |placement| area.after(placement) as i64)
+}
+
+// The placement that gets a block closest to the enemy
+pub fn distance_only(game: &Game, placements: &[Placement]) -> usize {
+    best(placements, |placement| {
+        let distance = placement.cells().map(|pos| game.analysis.enemy_distance(&pos)).min().unwrap_or(usize::MAX);
+        -(distance.min(i64::MAX as usize) as i64)
+    })
+}
+
+// Index of the placement with the highest score, the first one on ties
+fn best(placements: &[Placement], mut score: impl FnMut(&Placement) -> i64) -> usize {
+    let mut best = (0, i64::MIN);
+    for (i, placement) in placements.iter().enumerate() {
+        let score = score(placement);
+        if score > best.1 {
+            best = (i, score);
+        }
+    }
+    best.0
+}
+
+// Empty cells closer to us than to the enemy, with the Chebyshev distance of the analysis of
+// the game. The cells claimed before the placement are counted once, then each placement only
+// visits the cells its blocks bring closer to us, from the blocks outwards: a cell at least as
+// close to our cells as to the blocks brings none of its neighbours closer either.
+struct ClaimedArea<'a> {
+    game: &'a Game,
+    before: usize,
+    // Steps from the blocks of the placement, usize::MAX for the cells it does not bring closer
+    distance: Vec<usize>,
+    visited: Vec<usize>,
+    queue: VecDeque<Pos>,
+}
+
+impl<'a> ClaimedArea<'a> {
+    fn new(game: &'a Game) -> Self {
+        let field = &game.field;
+        let before = field.cells.positions().filter(|pos| Self::claimed(game, pos, usize::MAX)).count();
+        let distance = vec![usize::MAX; field.width() * field.height()];
+        ClaimedArea { game, before, distance, visited: Vec::new(), queue: VecDeque::new() }
+    }
+
+    // Whether the cell is empty and closer to us than to the enemy, with the blocks at the
+    // given distance
+    fn claimed(game: &Game, pos: &Pos, from_blocks: usize) -> bool {
+        let analysis = &game.analysis;
+        game.field.cells[pos].is_empty() && from_blocks.min(analysis.player_distance(pos)) < analysis.enemy_distance(pos)
+    }
+
+    fn index(&self, pos: &Pos) -> usize {
+        pos.y * self.game.field.width() + pos.x
+    }
+
+    // Claimed cells once the placement is made
+    fn after(&mut self, placement: &Placement) -> usize {
+        let (game, mut area) = (self.game, self.before);
+        for block in placement.cells() {
+            // The block covers the cell
+            area -= usize::from(Self::claimed(game, &block, usize::MAX));
+            let i = self.index(&block);
+            self.distance[i] = 0;
+            self.visited.push(i);
+            self.queue.push_back(block);
+        }
+        while let Some(pos) = self.queue.pop_front() {
+            let next = self.distance[self.index(&pos)] + 1;
+            for n in game.field.cells.neighbours8(&pos) {
+                let i = self.index(&n);
+                if next >= self.distance[i] || next >= game.analysis.player_distance(&n) {
+                    continue;
+                }
+                area += usize::from(!Self::claimed(game, &n, usize::MAX) && Self::claimed(game, &n, next));
+                self.distance[i] = next;
+                self.visited.push(i);
+                self.queue.push_back(n);
+            }
+        }
+        for i in self.visited.drain(..) {
+            self.distance[i] = usize::MAX;
+        }
+        area
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GAME: &str = "$$$ exec p1 : [baseline_bots]
+Anfield 6 4:
+    012345
+000 ......
+001 .@....
+002 ......
+003 .....$
+Piece 2 1:
+OO
+";
+
+    fn answers(choose: impl FnMut(&Game, &[Placement]) -> usize) -> String {
+        let mut output = Vec::new();
+        play(GAME.as_bytes(), &mut output, choose);
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_each_bot_answers_legally() {
+        // The domino covers the `@` with its right or its left block
+        let legal = ["0 1\n", "1 1\n"];
+        assert_eq!(answers(first_fit), "0 1\n");
+        assert!(legal.contains(&answers(random_legal()).as_str()));
+        assert!(legal.contains(&answers(max_area).as_str()));
+        // Toward the `$`, to its bottom right
+        assert_eq!(answers(distance_only), "1 1\n");
+    }
+
+    #[test]
+    fn test_max_area_claims_toward_the_enemy() {
+        // Covering the `@` with the left block puts the right one closer to the `$`
+        let wide = "$$$ exec p1 : [baseline_bots]
+Anfield 9 3:
+    012345678
+000 .........
+001 .@.......
+002 .......$.
+Piece 2 1:
+OO
+";
+        let answer = |choose: fn(&Game, &[Placement]) -> usize| {
+            let mut output = Vec::new();
+            play(wide.as_bytes(), &mut output, choose);
+            String::from_utf8(output).unwrap()
+        };
+        assert_eq!(answer(first_fit), "0 1\n");
+        assert_eq!(answer(max_area), "1 1\n");
+    }
+
+    #[test]
+    fn test_answers_0_0_when_stuck() {
+        // No cell of ours left to cover
+        let stuck = GAME.replace("001 .@....", "001 ......");
+        let mut output = Vec::new();
+        play(stuck.as_bytes(), &mut output, first_fit);
+        assert_eq!(String::from_utf8(output).unwrap(), "0 0\n");
+    }
+}
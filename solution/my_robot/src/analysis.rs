@@ -0,0 +1,222 @@
+use crate::field::{Field, Pos};
+
+// Connectivity of the empty cells. Two empty cells are connected when they are neighbours,
+// diagonals included, as pieces are not always connected. An articulation point is an empty
+// cell whose removal splits its region: once one of our pieces covers it, the parts on its
+// other side that no enemy cell touches can only be filled by us.
+
+// An empty cell that seals `sealed` empty cells off from the enemy once it is ours
+#[derive(Debug, Clone)]
+pub struct ChokePoint {
+    pub pos: Pos,
+    pub sealed: usize,
+}
+
+// Empty cells and their empty neighbours, as indexes y * width + x
+struct Graph {
+    width: usize,
+    neighbours: Vec<Vec<usize>>,
+    empty: Vec<bool>,
+    touches_player: Vec<bool>,
+    touches_enemy: Vec<bool>,
+}
+
+impl Graph {
+    fn new(field: &Field) -> Self {
+        let (width, height) = (field.width(), field.height());
+        let mut graph = Graph {
+            width,
+            neighbours: vec![Vec::new(); width * height],
+            empty: vec![false; width * height],
+            touches_player: vec![false; width * height],
+            touches_enemy: vec![false; width * height],
+        };
+        for pos in field.cells.positions() {
+            if !field.cells[&pos].is_empty() {
+                continue;
+            }
+            let i = pos.y * width + pos.x;
+            graph.empty[i] = true;
+            for neighbour in field.cells.neighbours8(&pos) {
+                let cell = field.cells[&neighbour];
+                if cell.is_empty() {
+                    graph.neighbours[i].push(neighbour.y * width + neighbour.x);
+                } else if cell.is_own() {
+                    graph.touches_player[i] = true;
+                } else if cell.is_enemy() {
+                    graph.touches_enemy[i] = true;
+                }
+            }
+        }
+        graph
+    }
+
+    fn pos(&self, i: usize) -> Pos {
+        Pos { y: i / self.width, x: i % self.width }
+    }
+}
+
+// Size of a part of a region and how many of its cells touch an enemy cell
+#[derive(Debug, Clone, Copy, Default)]
+struct Part {
+    cells: usize,
+    enemy: usize,
+}
+
+// Cells of the regions touching both players that seal at least one cell: the articulation
+// points, and the last cell of a region next to the enemy
+pub fn choke_points(field: &Field) -> Vec<ChokePoint> {
+    let graph = Graph::new(field);
+    let n = graph.empty.len();
+    const UNSEEN: usize = usize::MAX;
+
+    let mut discovered = vec![UNSEEN; n];
+    let mut low = vec![0; n];
+    let mut subtree = vec![Part::default(); n];
+    // Parts split off each cell, the subtrees of its children that cannot reach above it
+    let mut cut_parts: Vec<Vec<Part>> = vec![Vec::new(); n];
+    let mut time = 0;
+    let mut choke_points = Vec::new();
+
+    for root in 0..n {
+        if !graph.empty[root] || discovered[root] != UNSEEN {
+            continue;
+        }
+
+        // Iterative depth first search, each entry is a cell and its next neighbour to visit
+        let mut component = Vec::new();
+        let mut stack = vec![(root, 0)];
+        discovered[root] = time;
+        low[root] = time;
+        time += 1;
+        while let Some(&mut (i, ref mut next)) = stack.last_mut() {
+            if let Some(&neighbour) = graph.neighbours[i].get(*next) {
+                *next += 1;
+                if discovered[neighbour] == UNSEEN {
+                    discovered[neighbour] = time;
+                    low[neighbour] = time;
+                    time += 1;
+                    stack.push((neighbour, 0));
+                } else {
+                    low[i] = low[i].min(discovered[neighbour]);
+                }
+                continue;
+            }
+
+            stack.pop();
+            component.push(i);
+            subtree[i].cells += 1;
+            subtree[i].enemy += graph.touches_enemy[i] as usize;
+            if let Some(&(parent, _)) = stack.last() {
+                low[parent] = low[parent].min(low[i]);
+                subtree[parent].cells += subtree[i].cells;
+                subtree[parent].enemy += subtree[i].enemy;
+                if low[i] >= discovered[parent] {
+                    cut_parts[parent].push(subtree[i]);
+                }
+            }
+        }
+
+        let total = subtree[root];
+        if !component.iter().any(|&i| graph.touches_player[i]) || total.enemy == 0 {
+            continue;
+        }
+        for &i in &component {
+            let mut parts = std::mem::take(&mut cut_parts[i]);
+            // The cells outside the split off subtrees are one more part
+            let rest = parts.iter().fold(
+                Part { cells: total.cells - 1, enemy: total.enemy - graph.touches_enemy[i] as usize },
+                |rest, part| Part { cells: rest.cells - part.cells, enemy: rest.enemy - part.enemy },
+            );
+            if rest.cells > 0 {
+                parts.push(rest);
+            }
+            let sealed = parts.iter().filter(|part| part.enemy == 0).map(|part| part.cells).sum();
+            if sealed > 0 {
+                choke_points.push(ChokePoint { pos: graph.pos(i), sealed });
+            }
+        }
+    }
+
+    choke_points.sort_by_key(|choke_point| (choke_point.pos.y, choke_point.pos.x));
+    choke_points
+}
+
+// Narrow corridors: choke points next to each other, diagonals included, grouped together.
+// Covering any cell of a corridor cuts it, the cells of a corridor are sorted by position.
+pub fn corridors(choke_points: &[ChokePoint]) -> Vec<Vec<ChokePoint>> {
+    let mut corridors = Vec::new();
+    let mut seen = vec![false; choke_points.len()];
+    for start in 0..choke_points.len() {
+        if seen[start] {
+            continue;
+        }
+        seen[start] = true;
+        let mut corridor = vec![choke_points[start].clone()];
+        let mut next = 0;
+        while next < corridor.len() {
+            let pos = corridor[next].pos.clone();
+            next += 1;
+            for (j, other) in choke_points.iter().enumerate() {
+                if !seen[j] && pos.y.abs_diff(other.pos.y) <= 1 && pos.x.abs_diff(other.pos.x) <= 1 {
+                    seen[j] = true;
+                    corridor.push(other.clone());
+                }
+            }
+        }
+        corridor.sort_by_key(|choke_point| (choke_point.pos.y, choke_point.pos.x));
+        corridors.push(corridor);
+    }
+    corridors
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(rows: &[&str]) -> Field {
+        Field::from_rows(rows, ('a', '@'))
+    }
+
+    #[test]
+    fn test_corridor_to_a_pocket_is_a_choke_point() {
+        // The pocket on the left is only reachable through the corridor of row 2
+        let field = field(&[
+            "...@@@@",
+            "...@@@@",
+            "......$",
+            "...@@@@",
+            "...@@@@",
+        ]);
+        let choke_points = choke_points(&field);
+        let sealed: Vec<(usize, usize, usize)> = choke_points.iter().map(|c| (c.pos.y, c.pos.x, c.sealed)).collect();
+        // The further from the pocket, the more of the corridor is sealed with it
+        assert_eq!(sealed, vec![(2, 3, 15), (2, 4, 16), (2, 5, 17)]);
+
+        let corridors = corridors(&choke_points);
+        assert_eq!(corridors.len(), 1);
+        assert_eq!(corridors[0].len(), 3);
+    }
+
+    #[test]
+    fn test_open_region_has_no_choke_point() {
+        let field = field(&[
+            "@....",
+            ".....",
+            "...$$",
+        ]);
+        assert!(choke_points(&field).is_empty());
+    }
+
+    #[test]
+    fn test_region_without_enemy_has_no_choke_point() {
+        let field = field(&[
+            "...@@@",
+            "......",
+            "...@@@",
+            "@@@@@@",
+            "$$$$$$",
+        ]);
+        assert!(choke_points(&field).is_empty());
+    }
+}
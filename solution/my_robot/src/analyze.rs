@@ -0,0 +1,174 @@
+use std::fmt::Write;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::process;
+
+use crate::game::*;
+use crate::reader::Reader;
+
+// `my_robot analyze <position file> [--seat 1|2] [--top N]` ranks every valid placement of a
+// position as the robot would score it on its first turn. The file holds a board block and a
+// piece block as the engine sends them, a `$$$ exec pN` line gives the seat when `--seat` does
+// not. Other lines are skipped, so a position can be pasted from a game log as it is.
+
+// Placements drawn as boards after the ranking
+const DEFAULT_TOP: usize = 3;
+
+struct Options {
+    path: String,
+    seat: Option<usize>,
+    top: usize,
+}
+
+fn parse_args(args: &[String]) -> Option<Options> {
+    let mut args = args.iter().skip(2);
+    let mut options = Options { path: String::new(), seat: None, top: DEFAULT_TOP };
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--seat" => options.seat = Some(args.next()?.parse().ok().filter(|seat| matches!(seat, 1 | 2))?),
+            "--top" => options.top = args.next()?.parse().ok()?,
+            _ if options.path.is_empty() => options.path = arg.clone(),
+            _ => return None,
+        }
+    }
+    (!options.path.is_empty()).then_some(options)
+}
+
+pub fn main(args: &[String]) {
+    let Some(options) = parse_args(args) else {
+        eprintln!("Usage: {} analyze <position file> [--seat 1|2] [--top N]", args[0]);
+        process::exit(1);
+    };
+    let file = File::open(&options.path).unwrap_or_else(|err| {
+        eprintln!("Failed to open {}: {}", options.path, err);
+        process::exit(1);
+    });
+    match analyze(&mut Reader::new(BufReader::new(file)), options.seat, options.top) {
+        Ok(report) => print!("{}", report),
+        Err(err) => {
+            eprintln!("{}: {}", options.path, err);
+            process::exit(1);
+        }
+    }
+}
+
+// Report of the position: the placements ranked by score with what each evaluator added, then
+// the `top` best ones drawn on the board
+pub fn analyze<R: BufRead>(reader: &mut Reader<R>, seat: Option<usize>, top: usize) -> Result<String, String> {
+    let (mut game, piece) = read_position(reader, seat)?;
+    let dialect = reader.dialect;
+
+    let mut report = String::new();
+    let symbol = game.player.symbol.1;
+    let Some((mut placements, _)) = game.score_placements(&piece) else {
+        let _ = writeln!(report, "player {} ({}): the piece fits nowhere", game.player._num, symbol);
+        return Ok(report);
+    };
+    let phase = Phase::detect(&game.analysis, true);
+    let _ = writeln!(report, "player {} ({}), {:?} phase, {} valid placements", game.player._num, symbol, phase, placements.len());
+
+    // Highest score first, ties in the order the robot would pick them
+    placements.reverse();
+    placements.sort_by_key(|placement| -placement.score);
+    let _ = writeln!(report, "{:>4}  {:<9} {:>6}  breakdown", "rank", "answer", "score");
+    for (rank, placement) in placements.iter().enumerate() {
+        let breakdown: Vec<String> = placement.breakdown.iter().map(|(source, value)| format!("{} {}", source, value)).collect();
+        let _ = writeln!(report, "{:>4}  {:<9} {:>6}  {}", rank + 1, answer(placement, dialect), placement.score, breakdown.join(", "));
+    }
+
+    for (rank, placement) in placements.iter().take(top).enumerate() {
+        let _ = writeln!(report, "\n#{} at {}, score {}", rank + 1, answer(placement, dialect), placement.score);
+        report.push_str(&draw(&game.field, placement));
+    }
+    Ok(report)
+}
+
+// Game of the next board of the input, seen from the seat, with the piece after the board. The
+// seat is the given one, else the one of the first `$$$ exec pN` line, else player 1.
+pub fn read_position<R: BufRead>(reader: &mut Reader<R>, seat: Option<usize>) -> Result<(Game, Piece), String> {
+    let mut seat = seat;
+    let mut dialect = reader.dialect;
+    let mut game: Option<Game> = None;
+    while let Some(line) = reader.next_line() {
+        if let Some(exec) = line.strip_prefix("$$$ exec p") {
+            seat = seat.or(exec.chars().next().and_then(|c| c.to_digit(10)).map(|seat| seat as usize));
+        } else if let Some(board_dialect) = Dialect::detect(line) {
+            dialect = board_dialect;
+            let (player, _) = Player::new(&format!("$$$ exec p{} :", seat.unwrap_or(1)));
+            let field = Field::new(line, dialect, player.symbol);
+            reader.dialect = dialect;
+            let mut new_game = Game::new(player, field);
+            new_game.update_field(reader);
+            game = Some(new_game);
+        } else if line.starts_with("Piece") {
+            if let Some(game) = game {
+                let mut piece = Piece::new(line, dialect);
+                piece.update(reader);
+                return Ok((game, piece));
+            }
+        }
+    }
+    Err(if game.is_some() { "no piece found after the board" } else { "no board found" }.to_string())
+}
+
+fn answer(placement: &Placement, dialect: Dialect) -> String {
+    let (x, y) = placement.answer();
+    dialect.answer(x, y)
+}
+
+// Board as the engine draws it, with the cells of the placement as `*`
+fn draw(field: &Field, placement: &Placement) -> String {
+    let mut board = String::from("    ");
+    board.extend((0..field.width()).map(|x| char::from(b'0' + (x % 10) as u8)));
+    board.push('\n');
+    for y in 0..field.height() {
+        let _ = write!(board, "{:03} ", y);
+        for x in 0..field.width() {
+            let covered = y.checked_sub(placement.pos.y)
+                .zip(x.checked_sub(placement.pos.x))
+                .and_then(|(dy, dx)| placement.piece.trimmed_cells.get(&Pos { y: dy, x: dx }))
+                .is_some_and(|&block| block);
+            board.push(if covered { '*' } else { field.cells[y][x].to_char(field.player_symbol) });
+        }
+        board.push('\n');
+    }
+    board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSITION: &str = "$$$ exec p2 : [robots/bender]
+Anfield 6 3:
+    012345
+000 @.....
+001 ......
+002 ....$.
+<got (O): [0, 0]
+Piece 2 1:
+OO
+";
+
+    #[test]
+    fn test_analyze_ranks_placements() {
+        let report = analyze(&mut Reader::new(POSITION.as_bytes()), None, 1).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        // Seat 2 from the exec line: the domino fits on either side of the `$`
+        assert_eq!(lines[0], "player 2 ($), Opening phase, 2 valid placements");
+        assert_eq!(lines[2..].iter().take_while(|line| !line.is_empty()).count(), 2);
+        let best_score: i32 = lines[2].split_whitespace().nth(3).unwrap().parse().unwrap();
+        let second_score: i32 = lines[3].split_whitespace().nth(3).unwrap().parse().unwrap();
+        assert!(best_score >= second_score);
+        // One board drawn, with the two cells of the domino
+        let board: Vec<&str> = lines.iter().skip_while(|line| !line.starts_with("#1")).skip(2).copied().collect();
+        assert_eq!(board.len(), 3);
+        assert_eq!(board.iter().map(|row| row.matches('*').count()).sum::<usize>(), 2);
+    }
+
+    #[test]
+    fn test_analyze_needs_a_piece() {
+        let board_only = POSITION.split("<got").next().unwrap();
+        assert!(analyze(&mut Reader::new(board_only.as_bytes()), Some(1), 1).is_err());
+    }
+}
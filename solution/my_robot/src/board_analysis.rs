@@ -0,0 +1,403 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::field::{Cell, Field, Pos};
+
+// Per cell facts about the board, kept across turns and updated from the cells taken since
+// the previous update. Cells are only ever taken, never emptied, so distances only shrink and
+// regions only split: the cost of an update follows the size of the change, not of the board.
+// Cells are neighbours diagonally too, as pieces are not always connected.
+
+const NO_REGION: usize = usize::MAX;
+// Straight steps from an enemy cell counted by `enclosing_divider`
+const MAX_STRAIGHT_STEPS: usize = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Owner {
+    Empty,
+    Player,
+    Enemy,
+}
+
+// Connected empty cells, and how many of them are next to each player
+#[derive(Debug, Clone, Default)]
+struct Region {
+    cells: usize,
+    player_frontier: usize,
+    enemy_frontier: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct BoardAnalysis {
+    width: usize,
+    height: usize,
+    owner: Vec<Owner>,
+    // Steps to the closest cell of each player, a diagonal step counts as one
+    player_distance: Vec<usize>,
+    enemy_distance: Vec<usize>,
+    // Empty cells next to a cell of each player
+    player_frontier: Vec<bool>,
+    enemy_frontier: Vec<bool>,
+    // Region label of each empty cell, labels of split regions are not reused
+    region: Vec<usize>,
+    regions: Vec<Region>,
+    // Fewest straight steps from an enemy cell, up to MAX_STRAIGHT_STEPS
+    enemy_straight_steps: Vec<usize>,
+    // Sums of the y and x of each player's cells, and their count
+    player_sum: (usize, usize, usize),
+    enemy_sum: (usize, usize, usize),
+    // Whether a cell of ours is next to an enemy cell, diagonals excluded
+    touching: bool,
+    // Cells taken by each player in the last update
+    new_player_cells: Vec<Pos>,
+    new_enemy_cells: Vec<Pos>,
+}
+
+impl BoardAnalysis {
+    pub fn new(field: &Field) -> Self {
+        let (width, height) = (field.width(), field.height());
+        let cells = width * height;
+        let mut analysis = BoardAnalysis {
+            width,
+            height,
+            owner: vec![Owner::Empty; cells],
+            player_distance: vec![usize::MAX; cells],
+            enemy_distance: vec![usize::MAX; cells],
+            player_frontier: vec![false; cells],
+            enemy_frontier: vec![false; cells],
+            region: vec![0; cells],
+            regions: vec![Region { cells, ..Region::default() }],
+            enemy_straight_steps: vec![usize::MAX; cells],
+            player_sum: (0, 0, 0),
+            enemy_sum: (0, 0, 0),
+            touching: false,
+            new_player_cells: Vec::new(),
+            new_enemy_cells: Vec::new(),
+        };
+        let taken: Vec<Pos> = field.cells.positions().filter(|pos| !field.cells[pos].is_empty()).collect();
+        analysis.update(field, &taken);
+        analysis
+    }
+
+    // Takes the cells changed since the previous update, as returned by `Field::update`
+    pub fn update(&mut self, field: &Field, changed: &[Pos]) {
+        self.new_player_cells.clear();
+        self.new_enemy_cells.clear();
+        let mut taken = Vec::new();
+        for pos in changed {
+            let i = pos.y * self.width + pos.x;
+            // The previous piece of a player is no longer fresh, its owner stays the same
+            let owner = match field.cells[pos] {
+                Cell::Empty => Owner::Empty,
+                Cell::Own { .. } => Owner::Player,
+                Cell::Enemy { .. } => Owner::Enemy,
+            };
+            if owner == Owner::Empty || self.owner[i] != Owner::Empty {
+                continue;
+            }
+            self.owner[i] = owner;
+            taken.push(i);
+            match owner {
+                Owner::Player => self.new_player_cells.push(pos.clone()),
+                _ => self.new_enemy_cells.push(pos.clone()),
+            }
+        }
+
+        // Taken cells leave their region
+        let mut split_labels = Vec::new();
+        for &i in &taken {
+            let label = std::mem::replace(&mut self.region[i], NO_REGION);
+            let region = &mut self.regions[label];
+            region.cells -= 1;
+            if std::mem::take(&mut self.player_frontier[i]) {
+                region.player_frontier -= 1;
+            }
+            if std::mem::take(&mut self.enemy_frontier[i]) {
+                region.enemy_frontier -= 1;
+            }
+            if !split_labels.contains(&label) {
+                split_labels.push(label);
+            }
+        }
+
+        for &i in &taken {
+            let owner = self.owner[i];
+            let (y, x) = (i / self.width, i % self.width);
+            let sum = if owner == Owner::Player { &mut self.player_sum } else { &mut self.enemy_sum };
+            *sum = (sum.0 + y, sum.1 + x, sum.2 + 1);
+            if owner == Owner::Enemy {
+                self.add_enemy_straight_steps(y, x);
+            }
+            for n in neighbours(self.width, self.height, i) {
+                match self.owner[n] {
+                    Owner::Empty => {
+                        let (frontier, region) = match owner {
+                            Owner::Player => (&mut self.player_frontier[n], &mut self.regions[self.region[n]].player_frontier),
+                            _ => (&mut self.enemy_frontier[n], &mut self.regions[self.region[n]].enemy_frontier),
+                        };
+                        if !*frontier {
+                            *frontier = true;
+                            *region += 1;
+                        }
+                    }
+                    other if other != owner && (n / self.width == y || n % self.width == x) => self.touching = true,
+                    _ => {}
+                }
+            }
+        }
+
+        let (player_cells, enemy_cells): (Vec<usize>, Vec<usize>) = taken.iter().partition(|&&i| self.owner[i] == Owner::Player);
+        lower_distances(&mut self.player_distance, self.width, self.height, &player_cells);
+        lower_distances(&mut self.enemy_distance, self.width, self.height, &enemy_cells);
+        for label in split_labels {
+            self.split_region(label, &taken);
+        }
+    }
+
+    fn add_enemy_straight_steps(&mut self, y: usize, x: usize) {
+        for (dy, dx) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            for steps in 1..=MAX_STRAIGHT_STEPS {
+                let (ny, nx) = (y as isize + dy * steps as isize, x as isize + dx * steps as isize);
+                if ny < 0 || nx < 0 || ny as usize >= self.height || nx as usize >= self.width {
+                    break;
+                }
+                let i = ny as usize * self.width + nx as usize;
+                self.enemy_straight_steps[i] = self.enemy_straight_steps[i].min(steps);
+            }
+        }
+    }
+
+    // Relabels the region if taking the cells may have split it. The region cannot have split
+    // when the empty cells around the taken ones are still connected close to them, as any path
+    // through the taken cells can go around them. Only otherwise is the whole region flooded.
+    fn split_region(&mut self, label: usize, taken: &[usize]) {
+        let mut boundary: Vec<usize> = Vec::new();
+        for &i in taken {
+            for n in neighbours(self.width, self.height, i) {
+                if self.region[n] == label && !boundary.contains(&n) {
+                    boundary.push(n);
+                }
+            }
+        }
+        if boundary.len() <= 1 {
+            return;
+        }
+
+        // Bounding box of the boundary cells, one cell wider on each side
+        let (mut top, mut left, mut bottom, mut right) = (usize::MAX, usize::MAX, 0, 0);
+        for &i in &boundary {
+            let (y, x) = (i / self.width, i % self.width);
+            (top, left, bottom, right) = (top.min(y), left.min(x), bottom.max(y), right.max(x));
+        }
+        let (top, left) = (top.saturating_sub(1), left.saturating_sub(1));
+        let (bottom, right) = ((bottom + 1).min(self.height - 1), (right + 1).min(self.width - 1));
+        let in_window = |i: usize| (top..=bottom).contains(&(i / self.width)) && (left..=right).contains(&(i % self.width));
+        let reached: HashSet<usize> = self.flood(boundary[0], |n| self.region[n] == label && in_window(n)).into_iter().collect();
+        if boundary.iter().all(|i| reached.contains(i)) {
+            return;
+        }
+
+        // Every part gets a new label, the old one is left empty
+        for &start in &boundary {
+            if self.region[start] != label {
+                continue;
+            }
+            let cells = self.flood(start, |n| self.region[n] == label);
+            let new_label = self.regions.len();
+            let mut region = Region { cells: cells.len(), ..Region::default() };
+            for &i in &cells {
+                self.region[i] = new_label;
+                region.player_frontier += self.player_frontier[i] as usize;
+                region.enemy_frontier += self.enemy_frontier[i] as usize;
+            }
+            self.regions.push(region);
+        }
+        self.regions[label] = Region::default();
+    }
+
+    // Cells reached from `start` through neighbours accepted by `accept`
+    fn flood(&self, start: usize, accept: impl Fn(usize) -> bool) -> Vec<usize> {
+        let mut reached = vec![start];
+        let mut seen = HashSet::from([start]);
+        let mut next = 0;
+        while next < reached.len() {
+            let i = reached[next];
+            next += 1;
+            for n in neighbours(self.width, self.height, i) {
+                if accept(n) && seen.insert(n) {
+                    reached.push(n);
+                }
+            }
+        }
+        reached
+    }
+
+    // Average position of the enemy cells, rounded down
+    pub fn enemy_center(&self) -> Pos {
+        let (y, x, count) = self.enemy_sum;
+        Pos { y: y / count.max(1), x: x / count.max(1) }
+    }
+
+    pub fn is_touching_enemy(&self) -> bool {
+        self.touching
+    }
+
+    // True when no empty cell reachable from our cells is next to an enemy cell: whatever is
+    // left to us can only be filled by us
+    pub fn is_sealed_off(&self) -> bool {
+        !self.regions.iter().any(|region| region.player_frontier > 0 && region.enemy_frontier > 0)
+    }
+
+    // Fewest straight steps from the empty cell to an enemy cell, if there are at most 3
+    pub fn enclosing_divider(&self, pos: &Pos) -> Option<usize> {
+        let i = pos.y * self.width + pos.x;
+        (self.owner[i] == Owner::Empty && self.enemy_straight_steps[i] <= MAX_STRAIGHT_STEPS).then_some(self.enemy_straight_steps[i])
+    }
+
+    pub fn player_distance(&self, pos: &Pos) -> usize {
+        self.player_distance[pos.y * self.width + pos.x]
+    }
+
+    pub fn enemy_distance(&self, pos: &Pos) -> usize {
+        self.enemy_distance[pos.y * self.width + pos.x]
+    }
+
+    // Whether one of our cells is next to the cell, diagonals excluded
+    pub fn touches_player(&self, pos: &Pos) -> bool {
+        let i = pos.y * self.width + pos.x;
+        neighbours(self.width, self.height, i)
+            .any(|n| self.owner[n] == Owner::Player && (n / self.width == pos.y || n % self.width == pos.x))
+    }
+
+    pub fn new_player_cells(&self) -> &[Pos] {
+        &self.new_player_cells
+    }
+
+    pub fn new_enemy_cells(&self) -> &[Pos] {
+        &self.new_enemy_cells
+    }
+}
+
+fn neighbours(width: usize, height: usize, i: usize) -> impl Iterator<Item = usize> {
+    let (y, x) = (i / width, i % width);
+    (y.saturating_sub(1)..=(y + 1).min(height - 1))
+        .flat_map(move |ny| (x.saturating_sub(1)..=(x + 1).min(width - 1)).map(move |nx| ny * width + nx))
+        .filter(move |&n| n != i)
+}
+
+// Lowers the distances with new cells at distance 0, only visiting the cells that get closer
+fn lower_distances(distance: &mut [usize], width: usize, height: usize, sources: &[usize]) {
+    let mut queue: VecDeque<usize> = VecDeque::new();
+    for &source in sources {
+        distance[source] = 0;
+        queue.push_back(source);
+    }
+    while let Some(i) = queue.pop_front() {
+        let next = distance[i] + 1;
+        for n in neighbours(width, height, i) {
+            if distance[n] > next {
+                distance[n] = next;
+                queue.push_back(n);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(rows: &[&str]) -> Field {
+        Field::from_rows(rows, ('a', '@'))
+    }
+
+    // Field and the cells that changed from `before` to `after`
+    fn changes(before: &[&str], after: &[&str]) -> (Field, Vec<Pos>) {
+        let mut changed = Vec::new();
+        for (y, (old, new)) in before.iter().zip(after).enumerate() {
+            for (x, (a, b)) in old.chars().zip(new.chars()).enumerate() {
+                if a != b {
+                    changed.push(Pos { y, x });
+                }
+            }
+        }
+        (field(after), changed)
+    }
+
+    #[test]
+    fn test_touching_enemy() {
+        let apart = field(&[
+            ".a..",
+            "....",
+            "..s.",
+            "....",
+        ]);
+        assert!(!BoardAnalysis::new(&apart).is_touching_enemy());
+
+        let adjacent = field(&[
+            ".as.",
+            "....",
+            "....",
+            "....",
+        ]);
+        assert!(BoardAnalysis::new(&adjacent).is_touching_enemy());
+    }
+
+    #[test]
+    fn test_update_matches_a_fresh_analysis() {
+        let before = [
+            "@.......",
+            "........",
+            "........",
+            ".......$",
+        ];
+        let after = [
+            "@.......",
+            ".aaaaa..",
+            "......s.",
+            ".....ss$",
+        ];
+        let mut analysis = BoardAnalysis::new(&field(&before));
+        let (after_field, changed) = changes(&before, &after);
+        analysis.update(&after_field, &changed);
+        let fresh = BoardAnalysis::new(&after_field);
+
+        assert_eq!(analysis.player_distance, fresh.player_distance);
+        assert_eq!(analysis.enemy_distance, fresh.enemy_distance);
+        assert_eq!(analysis.player_frontier, fresh.player_frontier);
+        assert_eq!(analysis.enemy_frontier, fresh.enemy_frontier);
+        assert_eq!(analysis.enemy_straight_steps, fresh.enemy_straight_steps);
+        assert_eq!(analysis.enemy_center(), fresh.enemy_center());
+        assert_eq!(analysis.new_player_cells().len(), 5);
+        assert_eq!(analysis.new_enemy_cells().len(), 3);
+        assert_eq!(analysis.player_distance(&Pos { y: 3, x: 0 }), 2);
+        assert_eq!(analysis.enemy_distance(&Pos { y: 0, x: 7 }), 2);
+        assert_eq!(analysis.enclosing_divider(&Pos { y: 2, x: 7 }), Some(1));
+        assert_eq!(analysis.enclosing_divider(&Pos { y: 3, x: 7 }), None);
+    }
+
+    #[test]
+    fn test_wall_splits_the_region() {
+        let before = [
+            "@.....",
+            "......",
+            "......",
+            ".....$",
+        ];
+        let mut analysis = BoardAnalysis::new(&field(&before));
+        assert!(!analysis.is_sealed_off());
+
+        // Walls from top to bottom leave us the left side
+        let after = [
+            "@.as..",
+            "..as..",
+            "..as..",
+            "..as.$",
+        ];
+        let (after_field, changed) = changes(&before, &after);
+        analysis.update(&after_field, &changed);
+        assert!(analysis.is_sealed_off());
+        assert_ne!(analysis.region[1], analysis.region[4]);
+        assert!(analysis.touches_player(&Pos { y: 1, x: 1 }));
+        assert!(!analysis.touches_player(&Pos { y: 1, x: 4 }));
+    }
+}
@@ -0,0 +1,274 @@
+use crate::field::Field;
+use crate::game::Placement;
+use crate::parallel;
+use crate::piece::Piece;
+
+// Once our empty region is sealed off from the enemy, the rest of the game is packing pieces
+// into it. Small regions are searched exactly (within a node budget) for the number of
+// placements each candidate still leaves us, using the shapes seen so far as piece model.
+
+// Largest region searched, bigger ones are left to the other evaluators
+const MAX_REGION_CELLS: usize = 40;
+// Search nodes per turn, shared by all candidate placements
+const NODE_BUDGET: usize = 30_000;
+// Number of distinct shapes in the piece model, the smallest ones seen so far
+const MODEL_SHAPES: usize = 6;
+// Score of one future placement, outweighs the other evaluators
+const FUTURE_PLACEMENT_SCORE: i32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Cell {
+    Free,
+    Mine,
+    Blocked,
+}
+
+// Board used by the search: only the free cells of our sealed region can be covered
+#[derive(Debug, Clone)]
+struct Board {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    region: Vec<usize>,
+    free: usize,
+}
+
+// Filled cells of a trimmed piece, as (dy, dx)
+type Shape = Vec<(usize, usize)>;
+
+// Adds the "endgame" score to every placement when our region is sealed and small,
+// returns whether it did
+pub fn evaluate_placement_for_endgame(field: &Field, placements: &mut [Placement], prev_pieces: &[Piece]) -> bool {
+    let Some(board) = sealed_region(field) else {
+        return false;
+    };
+    let Some(current) = placements.first().map(|placement| placement.piece) else {
+        return false;
+    };
+    let shapes = piece_model(prev_pieces.iter().chain(std::iter::once(current)));
+
+    // Each placement has its own budget, so the result does not depend on how they are split
+    // across threads
+    let budget = (NODE_BUDGET / placements.len()).max(50);
+    parallel::for_each_chunk(placements, |chunk| {
+        for placement in chunk {
+            let mut board = board.clone();
+            for (dy, dx) in shape_of(placement.piece) {
+                let i = (placement.pos.y + dy) * board.width + placement.pos.x + dx;
+                if board.cells[i] == Cell::Free {
+                    board.free -= 1;
+                }
+                board.cells[i] = Cell::Mine;
+            }
+            let future = Search { shapes: &shapes, nodes: 0, budget, best: 0 }.run(&mut board);
+            placement.add_score("endgame", future as i32 * FUTURE_PLACEMENT_SCORE);
+        }
+    });
+    true
+}
+
+// Empty cells reachable from our cells, if there are some, none of them is next to an enemy
+// cell and there are at most MAX_REGION_CELLS of them. Cells are neighbours diagonally too, as pieces are
+// not always connected.
+fn sealed_region(field: &Field) -> Option<Board> {
+    let (width, height) = (field.width(), field.height());
+    let neighbours = |i: usize| {
+        let (y, x) = (i / width, i % width);
+        let rows = y.saturating_sub(1)..=(y + 1).min(height - 1);
+        rows.flat_map(move |ny| (x.saturating_sub(1)..=(x + 1).min(width - 1)).map(move |nx| ny * width + nx))
+    };
+    let cell_at = |i: usize| field.cells[i / width][i % width];
+
+    let mut cells = vec![Cell::Blocked; width * height];
+    let mut stack = Vec::new();
+    for (i, cell) in cells.iter_mut().enumerate() {
+        if cell_at(i).is_own() {
+            *cell = Cell::Mine;
+            stack.extend(neighbours(i).filter(|&n| cell_at(n).is_empty()));
+        }
+    }
+
+    let mut region = Vec::new();
+    while let Some(i) = stack.pop() {
+        if cells[i] != Cell::Blocked || !cell_at(i).is_empty() {
+            continue;
+        }
+        if neighbours(i).any(|n| cell_at(n).is_enemy()) || region.len() == MAX_REGION_CELLS {
+            return None;
+        }
+        cells[i] = Cell::Free;
+        region.push(i);
+        stack.extend(neighbours(i).filter(|&n| cell_at(n).is_empty()));
+    }
+
+    if region.is_empty() {
+        return None;
+    }
+    let free = region.len();
+    Some(Board { width, height, cells, region, free })
+}
+
+// Distinct shapes of the pieces, smallest first. Single cells are left out: they fit on any
+// of our cells without using the region.
+fn piece_model<'a, I: Iterator<Item = &'a Piece>>(pieces: I) -> Vec<Shape> {
+    let mut shapes: Vec<Shape> = Vec::new();
+    for piece in pieces {
+        let shape = shape_of(piece);
+        if shape.len() > 1 && !shapes.contains(&shape) {
+            shapes.push(shape);
+        }
+    }
+    shapes.sort_by_key(|shape| shape.len());
+    shapes.truncate(MODEL_SHAPES);
+    shapes
+}
+
+fn shape_of(piece: &Piece) -> Shape {
+    let mut shape = Vec::new();
+    for (dy, row) in piece.trimmed_cells.rows().enumerate() {
+        for (dx, &block) in row.iter().enumerate() {
+            if block {
+                shape.push((dy, dx));
+            }
+        }
+    }
+    shape
+}
+
+// Depth first search for the longest sequence of placements, cut when it cannot beat the
+// best one found or when the node budget is spent
+struct Search<'a> {
+    shapes: &'a [Shape],
+    nodes: usize,
+    budget: usize,
+    best: usize,
+}
+
+impl Search<'_> {
+    fn run(mut self, board: &mut Board) -> usize {
+        self.search(board, 0);
+        self.best
+    }
+
+    fn search(&mut self, board: &mut Board, depth: usize) {
+        self.nodes += 1;
+        self.best = self.best.max(depth);
+        // Every placement covers at least one free cell per cell beyond the overlapping one
+        let min_free_cells = self.shapes.iter().map(|shape| shape.len() - 1).min().unwrap_or(0);
+        if self.nodes >= self.budget || min_free_cells == 0 || depth + board.free / min_free_cells <= self.best {
+            return;
+        }
+
+        for shape in self.shapes {
+            for (y, x) in moves(board, shape) {
+                let covered: Vec<usize> = shape.iter().map(|(dy, dx)| (y + dy) * board.width + x + dx).collect();
+                let free: Vec<usize> = covered.iter().copied().filter(|&i| board.cells[i] == Cell::Free).collect();
+                for &i in &free {
+                    board.cells[i] = Cell::Mine;
+                }
+                board.free -= free.len();
+
+                self.search(board, depth + 1);
+
+                for &i in &free {
+                    board.cells[i] = Cell::Free;
+                }
+                board.free += free.len();
+                if self.nodes >= self.budget {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+// Legal positions of the shape covering at least one free cell, as (y, x) of its top left
+fn moves(board: &Board, shape: &Shape) -> Vec<(usize, usize)> {
+    let mut moves = Vec::new();
+    for &i in &board.region {
+        if board.cells[i] != Cell::Free {
+            continue;
+        }
+        let (y, x) = (i / board.width, i % board.width);
+        for &(dy, dx) in shape {
+            if y >= dy && x >= dx && is_legal(board, shape, y - dy, x - dx) {
+                moves.push((y - dy, x - dx));
+            }
+        }
+    }
+    moves.sort_unstable();
+    moves.dedup();
+    moves
+}
+
+// Same rule as `Game::check_placement`: exactly one cell on ours, the others free
+fn is_legal(board: &Board, shape: &Shape, y: usize, x: usize) -> bool {
+    let mut overlap = 0;
+    for &(dy, dx) in shape {
+        if y + dy >= board.height || x + dx >= board.width {
+            return false;
+        }
+        match board.cells[(y + dy) * board.width + x + dx] {
+            Cell::Mine => overlap += 1,
+            Cell::Blocked => return false,
+            Cell::Free => {}
+        }
+    }
+    overlap == 1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Pos;
+
+    fn field(rows: &[&str]) -> Field {
+        Field::from_rows(rows, ('a', '@'))
+    }
+
+    fn placement(piece: &Piece, x: usize, y: usize) -> Placement<'_> {
+        Placement { pos: Pos { x, y }, score: 0, piece, breakdown: Vec::new() }
+    }
+
+    #[test]
+    fn test_region_touching_enemy_is_not_sealed() {
+        let open = field(&[
+            "@@..",
+            "@@..",
+            "...$",
+        ]);
+        assert!(sealed_region(&open).is_none());
+
+        let sealed = field(&[
+            "@@..",
+            "@@@@",
+            "$$$$",
+        ]);
+        let board = sealed_region(&sealed).unwrap();
+        assert_eq!(board.free, 2);
+
+        // Without an empty cell to reach there is nothing to pack
+        let full = field(&[
+            "@@@@",
+            "$$$$",
+        ]);
+        assert!(sealed_region(&full).is_none());
+    }
+
+    #[test]
+    fn test_endgame_keeps_room_for_later_pieces() {
+        // No enemy cell at all, so the whole board is our sealed region
+        let field = field(&[
+            "@@.@.",
+            "...@@",
+        ]);
+        // Placed on the left the L leaves room for another one on the right, in the
+        // middle it leaves two single cells
+        let l = Piece::from_rows(&["O.", "OO"]);
+        let mut placements = vec![placement(&l, 0, 0), placement(&l, 1, 0)];
+
+        assert!(evaluate_placement_for_endgame(&field, &mut placements, &[]));
+        assert_eq!(placements[0].score, FUTURE_PLACEMENT_SCORE);
+        assert_eq!(placements[1].score, 0);
+    }
+}
@@ -1,66 +1,241 @@
-use std::io::Error;
-use crate::grid::{Size, Grid};
+use std::fmt;
+use std::io::BufRead;
+pub use crate::grid::Pos;
+use crate::grid::{Grid, Size};
+use crate::protocol::Dialect;
+use crate::reader::Reader;
 
-#[derive(Debug, Clone)]
-pub struct Field {
-    pub size: Size,
-    pub cells: Vec<Vec<char>>,
+// A cell of the board, seen from our side. A fresh cell belongs to the last piece its owner
+// placed, the engine draws it in lowercase.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Empty,
+    Own { fresh: bool },
+    Enemy { fresh: bool },
+}
+
+impl Cell {
+    // Cell drawn as `c` for the player drawn as `player_symbol`, None for a character that is
+    // not a cell
+    pub fn from_char(c: char, player_symbol: (char, char)) -> Option<Cell> {
+        let enemy_symbol = enemy_symbol(player_symbol);
+        match c {
+            '.' => Some(Cell::Empty),
+            _ if c == player_symbol.0 => Some(Cell::Own { fresh: true }),
+            _ if c == player_symbol.1 => Some(Cell::Own { fresh: false }),
+            _ if c == enemy_symbol.0 => Some(Cell::Enemy { fresh: true }),
+            _ if c == enemy_symbol.1 => Some(Cell::Enemy { fresh: false }),
+            _ => None,
+        }
+    }
+
+    // Character of the cell for the player drawn as `player_symbol`, the inverse of `from_char`
+    pub fn to_char(self, player_symbol: (char, char)) -> char {
+        let enemy_symbol = enemy_symbol(player_symbol);
+        match self {
+            Cell::Empty => '.',
+            Cell::Own { fresh: true } => player_symbol.0,
+            Cell::Own { fresh: false } => player_symbol.1,
+            Cell::Enemy { fresh: true } => enemy_symbol.0,
+            Cell::Enemy { fresh: false } => enemy_symbol.1,
+        }
+    }
+
+    pub fn is_empty(self) -> bool {
+        self == Cell::Empty
+    }
+
+    pub fn is_own(self) -> bool {
+        matches!(self, Cell::Own { .. })
+    }
+
+    pub fn is_enemy(self) -> bool {
+        matches!(self, Cell::Enemy { .. })
+    }
+
+    // The same cell seen from the enemy side
+    pub fn flipped(self) -> Cell {
+        match self {
+            Cell::Empty => Cell::Empty,
+            Cell::Own { fresh } => Cell::Enemy { fresh },
+            Cell::Enemy { fresh } => Cell::Own { fresh },
+        }
+    }
+}
+
+fn enemy_symbol(player_symbol: (char, char)) -> (char, char) {
+    if player_symbol == ('a', '@') { ('s', '$') } else { ('a', '@') }
+}
+
+// Drawn with the classic symbols, from our side: `O` ours and `X` the enemy's
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let c = match self {
+            Cell::Empty => '.',
+            Cell::Own { fresh: true } => 'o',
+            Cell::Own { fresh: false } => 'O',
+            Cell::Enemy { fresh: true } => 'x',
+            Cell::Enemy { fresh: false } => 'X',
+        };
+        write!(f, "{}", c)
+    }
 }
 
 #[derive(Debug, Clone)]
-pub struct Pos {
-    pub y: usize,
-    pub x: usize,
+pub struct Field {
+    pub cells: Grid<Cell>,
+    // Symbols of our cells, as (fresh, older)
+    pub player_symbol: (char, char),
 }
 
 impl Field {
-    pub fn new(header: &str) -> Self {
+    pub fn new(header: &str, dialect: Dialect, player_symbol: (char, char)) -> Self {
+        Field { cells: Grid::new(dialect.size(header), Cell::Empty), player_symbol }
+    }
 
-        let parts: Vec<&str> = header
-            .trim_end_matches(':')
-            .split_whitespace()
+    // Board drawn with the 01 edu symbols, one string per row
+    #[cfg(test)]
+    pub fn from_rows(rows: &[&str], player_symbol: (char, char)) -> Self {
+        let cells = rows.iter()
+            .map(|row| row.chars().map(|c| Cell::from_char(c, player_symbol).expect("Invalid cell")).collect())
             .collect();
-        let col_count: usize = parts[1].parse().expect("Invalid col count");
-        let row_count: usize = parts[2].parse().expect("Invalid row count");
-
-        let cells = vec![vec!['.'; col_count]; row_count];
-        Field { size: Size{ height: row_count, width: col_count }, cells }
+        Field { cells: Grid::from_rows(cells), player_symbol }
     }
 
-    pub fn update<I: Iterator<Item = Result<String, Error>>>(&mut self, lines: &mut I) {
-        let _ = lines.next(); // skip column headers
+    pub fn size(&self) -> &Size { self.cells.size() }
+    pub fn width(&self) -> usize { self.cells.width() }
+    pub fn height(&self) -> usize { self.cells.height() }
+
+    // Reads the board straight into the cells and puts the cells that changed since the previous
+    // board in `changed`, emptied first. The column header and the row numbers are checked
+    // against the size.
+    pub fn update<R: BufRead>(&mut self, reader: &mut Reader<R>, changed: &mut Vec<Pos>) {
+        let width = self.width();
+        let dialect = reader.dialect;
+        match reader.next_line() {
+            Some(header) if is_column_header(header.trim_end(), width) => {}
+            Some(header) => panic!("Invalid column header {:?} for width {}", header, width),
+            None => panic!("Unexpected end of input while reading the column header"),
+        }
 
+        changed.clear();
         for r in 0..self.height() {
-            let line = match lines.next() {
-                Some(Ok(l)) => l.trim_end().to_string(),
-                _ => panic!("Unexpected end of input while reading row {}", r),
+            let line = match reader.next_line() {
+                Some(line) => line.trim_end(),
+                None => panic!("Unexpected end of input while reading row {}", r),
             };
-
-            if line.chars().count() < 4 + self.width() {
-                panic!( "Invalid row {}: expected at least {} characters, got {}",
-                    r, 4 + self.width(), line.chars().count());
+            let cells = match (line.get(..4), line.get(4..)) {
+                (Some(prefix), Some(cells)) if is_row_prefix(prefix, r) => cells,
+                _ => panic!("Invalid row {}: expected the prefix {:03}, got {:?}", r, r, line),
+            };
+            if cells.chars().count() < width {
+                panic!("Invalid row {}: expected at least {} columns, got {}",
+                    r, width, cells.chars().count());
             }
 
-            let row_data: Vec<char> = line[4..].chars().take(self.width()).collect();
-            if row_data.len() != self.width() {
-                panic!( "Row {} has incorrect number of columns: expected {}, got {}",
-                    r, self.width(), row_data.len());
+            for (x, (cell, c)) in self.cells.row_mut(r).iter_mut().zip(cells.chars()).enumerate() {
+                let new = match Cell::from_char(dialect.board_cell(c), self.player_symbol) {
+                    Some(new) => new,
+                    None => panic!("Invalid cell {:?} in row {}", c, r),
+                };
+                if *cell != new {
+                    *cell = new;
+                    changed.push(Pos { y: r, x });
+                }
             }
-            self.cells_mut()[r] = row_data;
         }
     }
 }
 
-impl Grid for Field {
-    fn height(&self) -> usize { self.size.height }
-    fn width(&self) -> usize { self.size.width }
-    fn cells(&self) -> &Vec<Vec<char>> { &self.cells }
-    fn cells_mut(&mut self) -> &mut Vec<Vec<char>> { &mut self.cells }
+// Column header of the board: four spaces, then the last digit of each column number
+fn is_column_header(line: &str, width: usize) -> bool {
+    line.len() == 4 + width
+        && line.starts_with("    ")
+        && line.bytes().skip(4).enumerate().all(|(x, digit)| digit == b'0' + (x % 10) as u8)
+}
+
+// Row number on three digits and a space
+fn is_row_prefix(prefix: &str, row: usize) -> bool {
+    let bytes = prefix.as_bytes();
+    bytes[3] == b' '
+        && bytes[..3].iter().all(u8::is_ascii_digit)
+        && bytes[..3].iter().fold(0, |n, &digit| n * 10 + (digit - b'0') as usize) == row
 }
-use std::fmt;
 
 impl fmt::Display for Field {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.print_grid())
+        write!(f, "{}", self.cells)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOARD: &str = "    01234567890
+000 ...........
+001 ..@........
+002 .........$.
+";
+
+    #[test]
+    fn test_update_returns_changed_cells() {
+        let mut field = Field::new("Anfield 11 3:", Dialect::Anfield, ('a', '@'));
+        let mut changed = Vec::new();
+        field.update(&mut Reader::new(BOARD.as_bytes()), &mut changed);
+        assert_eq!(changed, vec![Pos { y: 1, x: 2 }, Pos { y: 2, x: 9 }]);
+        assert_eq!(field.cells[1][2], Cell::Own { fresh: false });
+
+        let next = BOARD.replace("001 ..@.", "001 .aa.");
+        field.update(&mut Reader::new(next.as_bytes()), &mut changed);
+        assert_eq!(changed, vec![Pos { y: 1, x: 1 }, Pos { y: 1, x: 2 }]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_update_plateau() {
+        let mut field = Field::new("Plateau 3 11:", Dialect::Plateau, ('a', '@'));
+        let board = BOARD.replace('@', "O").replace('$', "x");
+        let mut reader = Reader::new(board.as_bytes());
+        reader.dialect = Dialect::Plateau;
+        field.update(&mut reader, &mut Vec::new());
+        assert_eq!((field.cells[1][2], field.cells[2][9]), (Cell::Own { fresh: false }, Cell::Enemy { fresh: true }));
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid cell")]
+    fn test_update_rejects_unknown_cells() {
+        let board = BOARD.replace('$', "#");
+        Field::new("Anfield 11 3:", Dialect::Anfield, ('a', '@')).update(&mut Reader::new(board.as_bytes()), &mut Vec::new());
+    }
+
+    #[test]
+    fn test_cell_from_char() {
+        let player_symbol = ('s', '$');
+        assert_eq!(Cell::from_char('s', player_symbol), Some(Cell::Own { fresh: true }));
+        assert_eq!(Cell::from_char('@', player_symbol), Some(Cell::Enemy { fresh: false }));
+        assert_eq!(Cell::from_char('.', player_symbol), Some(Cell::Empty));
+        // Unknown characters are not the enemy
+        assert_eq!(Cell::from_char('X', player_symbol), None);
+        for c in ['.', 'a', '@', 's', '$'] {
+            assert_eq!(Cell::from_char(c, player_symbol).map(|cell| cell.to_char(player_symbol)), Some(c));
+        }
+        assert_eq!(Cell::Own { fresh: true }.flipped(), Cell::Enemy { fresh: true });
+
+        assert!(Cell::Own { fresh: false }.is_own() && !Cell::Own { fresh: false }.is_enemy());
+        assert!(Cell::Enemy { fresh: true }.is_enemy() && !Cell::Enemy { fresh: true }.is_own());
+        assert!(Cell::Empty.is_empty() && !Cell::Empty.is_own() && !Cell::Empty.is_enemy());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid row 1")]
+    fn test_update_checks_row_numbers() {
+        let board = BOARD.replace("001 ", "002 ");
+        Field::new("Anfield 11 3:", Dialect::Anfield, ('a', '@')).update(&mut Reader::new(board.as_bytes()), &mut Vec::new());
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid column header")]
+    fn test_update_checks_column_header() {
+        Field::new("Anfield 12 3:", Dialect::Anfield, ('a', '@')).update(&mut Reader::new(BOARD.as_bytes()), &mut Vec::new());
+    }
+}
@@ -2,90 +2,155 @@ pub use crate::field::*;
 pub use crate::piece::*;
 pub use crate::player::*;
 pub use crate::utils::*;
+pub use crate::trace::*;
+pub use crate::phase::*;
+pub use crate::opponent::*;
+pub use crate::opening::*;
+pub use crate::board_analysis::*;
+pub use crate::protocol::*;
+use crate::parallel;
+use crate::reader::Reader;
+
+use std::io::BufRead;
 
 #[derive(Debug, Clone)]
 pub struct Game {
     pub player: Player,
-    pub enemy: Player,
     pub field: Field,
+    // Distances, frontiers and regions of the field, updated with it
+    pub analysis: BoardAnalysis,
+    // Pieces of the previous turns
     pub pieces: Vec<Piece>,
     pub turns: usize,
+    pub trace: Option<Trace>,
+    pub opponent: Profile,
+    // Book opening of the map, found on the first turn
+    pub opening: Option<Opening>,
+    // Cells changed by the last board, kept so that reading a board does not allocate
+    changed: Vec<Pos>,
 }
 
 #[derive(Debug, Clone)]
-pub struct Placement {
+pub struct Placement<'p> {
     pub pos: Pos,
     pub score: i32,
-    pub piece: Piece,
+    // Piece of the turn, borrowed so that the candidates of a turn do not copy it
+    pub piece: &'p Piece,
+    // score contributions by source, in the order they were added
+    pub breakdown: Vec<(&'static str, i32)>,
+}
+
+impl Placement<'_> {
+    // Adds to the score and keeps track of where it came from
+    pub fn add_score(&mut self, source: &'static str, value: i32) {
+        self.score += value;
+        match self.breakdown.iter_mut().find(|(name, _)| *name == source) {
+            Some((_, total)) => *total += value,
+            None => self.breakdown.push((source, value)),
+        }
+    }
+
+    // Cells of the board the blocks of the piece go on
+    pub fn cells(&self) -> impl Iterator<Item = Pos> + '_ {
+        self.piece.trimmed_cells.positions()
+            .filter(|block| self.piece.trimmed_cells[block])
+            .map(|block| Pos { y: self.pos.y + block.y, x: self.pos.x + block.x })
+    }
+
+    // Position as answered to the game engine, relative to the untrimmed piece
+    pub fn answer(&self) -> (i32, i32) {
+        (self.pos.x as i32 - self.piece.offset.1 as i32, self.pos.y as i32 - self.piece.offset.0 as i32)
+    }
 }
 
 impl Game {
-    pub fn new(player: Player, enemy: Player, field: Field) -> Self {
+    pub fn new(player: Player, field: Field) -> Self {
+        let analysis = BoardAnalysis::new(&field);
         Self {
             player,
-            enemy,
             field,
+            analysis,
             pieces: Vec::new(),
             turns: 0,
+            trace: None,
+            opponent: Profile::default(),
+            opening: None,
+            changed: Vec::new(),
         }
     }
-    // try to place the piece and return the best position or (0,0) if no valid placement found
-    pub fn place_piece(&mut self, p: Piece) -> (i32, i32) {
-        self.turns += 1;
 
-        if p.trimmed_size.height > self.field.size.height || p.trimmed_size.width > self.field.size.width {
+    // Reads the next board into the field and updates the analysis with the cells that changed
+    pub fn update_field<R: BufRead>(&mut self, reader: &mut Reader<R>) {
+        self.field.update(reader, &mut self.changed);
+        self.analysis.update(&self.field, &self.changed);
+    }
+
+    // try to place the piece and return the best position or (0,0) if no valid placement found
+    pub fn place_piece(&mut self, p: &Piece) -> (i32, i32) {
+        let Some((possible_placements, best)) = self.score_placements(p) else {
             return (0, 0);
-        }
+        };
 
-        let enemy_pos = get_average_pos(&self.field, self.player.symbol, true);
-        // let player_pos = get_average_pos(&self.field, self.player.symbol, false);
+        if let Some(trace) = &self.trace {
+            trace.write_turn(self.turns, &possible_placements, &best);
+        }
 
-        // Changed back to keeping a full list of placements for the sake of evaluating scores of possible placements relative to each other
-        // One of the main reasons for this is to be able to evaluate how much closer a placement is getting to the enemy relative to possible placements starting from other positions
-        let mut possible_placements: Vec<Placement> = Vec::new();
+        self.pieces.push(p.clone());
+        self.player.score += 1;
+        best.answer()
+    }
 
-        for y in p.offset.0..=self.field.size.height - p.trimmed_size.height {
-            for x in p.offset.1..=self.field.size.width - p.trimmed_size.width {
-                if let Some(p_valid) = self.check_placement(&p, Pos { x, y }) {
-                    possible_placements.push(p_valid);
-                }
-            }
+    // Scores every valid placement of the piece for this turn and returns them with a copy of
+    // the best one, None if the piece fits nowhere
+    pub fn score_placements<'p>(&mut self, p: &'p Piece) -> Option<(Vec<Placement<'p>>, Placement<'p>)> {
+        self.turns += 1;
+        self.opponent.observe(&self.field, &self.analysis);
+        if self.turns == 1 {
+            self.opening = Opening::find(&self.field);
         }
 
-        if possible_placements.is_empty() {
-            return (0, 0);
+        let mut possible_placements = self.valid_placements(p);
+        let phase = Phase::detect(&self.analysis, !possible_placements.is_empty());
+        if phase == Phase::Stuck {
+            return None;
         }
 
-        let best: Placement =  evaluate_placements(&self.field, possible_placements, enemy_pos, self.turns, self.player.symbol, &self.pieces);
-
-        self.pieces.push(p);
-        self.player.score += 1;
-        (best.pos.x as i32 - best.piece.offset.1 as i32, best.pos.y as i32 - best.piece.offset.0 as i32)
+        let weights = self.opponent.style().counter_weights(phase);
+        let book_target = self.opening.as_ref().and_then(|opening| opening.target(&self.field, &self.analysis, self.turns));
+        let best: Placement =  evaluate_placements(&self.field, &self.analysis, &mut possible_placements, book_target, &weights, &self.pieces);
+        Some((possible_placements, best))
     }
 
-    // Check if placing the piece at the given position is valid
-    pub fn check_placement(&self, piece: &Piece, pos: Pos) -> Option<Placement> {
-        let mut overlap = 0;
-
-        for (dy, row) in piece.trimmed_cells.iter().enumerate() {
-            for (dx, c) in row.iter().enumerate() {
-                let cell = self.field.cells[dy + pos.y][dx + pos.x];
-                if *c == 'O' && self.player.is_mine(&cell) {
-                    overlap += 1;
-                    if overlap > 1 {
-                        return None;
-                    }
-                } else if *c == 'O' && self.enemy.is_mine(&cell) {
-                    return None;
-                }
+    // Every valid placement of the piece, scored by its cells only. Positions are those of the
+    // trimmed piece, from 0: the padding of the piece may hang off the board.
+    pub fn valid_placements<'p>(&self, p: &'p Piece) -> Vec<Placement<'p>> {
+        if p.trimmed_size.height > self.field.height() || p.trimmed_size.width > self.field.width() {
+            return Vec::new();
+        }
+
+        // Changed back to keeping a full list of placements for the sake of evaluating scores of possible placements relative to each other
+        // One of the main reasons for this is to be able to evaluate how much closer a placement is getting to the enemy relative to possible placements starting from other positions
+        // Positions are checked across threads, in the same order as a single thread would
+        let mut positions: Vec<Pos> = Vec::new();
+        for y in 0..=self.field.height() - p.trimmed_size.height {
+            for x in 0..=self.field.width() - p.trimmed_size.width {
+                positions.push(Pos { x, y });
             }
         }
-        if overlap != 1 {
+        parallel::map(&positions, |pos| self.check_placement(p, pos.clone()))
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    // Check if placing the piece at the given position is valid
+    pub fn check_placement<'p>(&self, piece: &'p Piece, pos: Pos) -> Option<Placement<'p>> {
+        if !is_valid_placement(&self.field, &piece.trimmed_cells, &pos, false) {
             return None;
         }
 
         let mut score = 0;
-        for (dy, row) in piece.trimmed_cells.iter().enumerate() {
+        for (dy, row) in piece.trimmed_cells.rows().enumerate() {
             for (dx, &piece_cell) in row.iter().enumerate() {
                 score += self.get_cell_score(
                     piece_cell,
@@ -96,39 +161,31 @@ impl Game {
                 );
             }
         }
-        Some(Placement {
+        let mut placement = Placement {
             pos,
-            score,
-            piece: piece.to_owned(),
-        })
+            score: 0,
+            piece,
+            breakdown: Vec::new(),
+        };
+        placement.add_score("cells", score);
+        Some(placement)
     }
 
-    pub fn get_cell_score(&self, piece_cell: char, cell_pos: Pos) -> i32 {
-        let will_place_here = piece_cell == 'O';
-
-        // For clarity
-        let (prev_y_cell, next_y_cell, prev_x_cell, next_x_cell) = get_adjacent_cells(&self.field, &cell_pos);
+    pub fn get_cell_score(&self, piece_cell: bool, cell_pos: Pos) -> i32 {
+        let will_place_here = piece_cell;
 
         match will_place_here {
             false => {
-                let cur_cell = self.field.cells[cell_pos.y][cell_pos.x];
-                if self.player.is_mine(&cur_cell) { 1 }
-                else if self.enemy.is_mine(&cur_cell) { 2 }
+                let cur_cell = self.field.cells[&cell_pos];
+                if cur_cell.is_own() { 1 }
+                else if cur_cell.is_enemy() { 2 }
                 else { 0 }
             },
             true => {
-                if prev_y_cell.is_some() &&
-                    self.enemy.is_mine( &prev_y_cell.unwrap() ) { 4 }
-
-                else if next_y_cell.is_some() &&
-                    self.enemy.is_mine( &next_y_cell.unwrap() ) { 4 } 
-
-                else if prev_x_cell.is_some() &&
-                    self.enemy.is_mine( &prev_x_cell.unwrap() ) { 4 }
-
-                else if next_x_cell.is_some() &&
-                    self.enemy.is_mine( &next_x_cell.unwrap() ) { 4 } 
-                else { 0 }
+                let touches_enemy = self.field.cells
+                    .neighbours4(&cell_pos)
+                    .any(|pos| self.field.cells[&pos].is_enemy());
+                if touches_enemy { 4 } else { 0 }
             },
         }
     }
@@ -137,7 +194,6 @@ impl Game {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::grid::Size;
 
     fn create_test_game() -> Game {
         let p1 = Player {
@@ -145,45 +201,20 @@ mod tests {
             symbol: ('a', '@'),
             score: 0,
         };
-        let p2 = Player {
-            _num: 2,
-            symbol: ('s', '$'),
-            score: 0,
-        };
 
         // Create a simple 4x4 field with some existing pieces
-        let field = Field {
-            size: Size {
-                width: 4,
-                height: 4,
-            },
-            cells: vec![
-                vec!['.', 'a', '.', '.'], // Player piece at (0,1)
-                vec!['.', '.', '.', '.'],
-                vec!['.', '.', 's', '.'], // Enemy piece at (2,2)
-                vec!['.', '.', '.', '.'],
-            ],
-        };
-
-        Game::new(p1, p2, field)
+        let field = Field::from_rows(&[
+            ".a..", // Player piece at (0,1)
+            "....",
+            "..s.", // Enemy piece at (2,2)
+            "....",
+        ], p1.symbol);
+
+        Game::new(p1, field)
     }
 
     fn create_test_piece() -> Piece {
-        let piece = Piece {
-            size: Size {
-                width: 2,
-                height: 2,
-            },
-            cells: vec![vec!['O', '.'], vec!['.', 'O']],
-            trimmed_size: Size {
-                width: 2,
-                height: 2,
-            },
-            trimmed_cells: vec![vec!['O', '.'], vec!['.', 'O']],
-            symbol_count: 2,
-            offset: (0, 0),
-        };
-        piece
+        Piece::from_rows(&["O.", ".O"])
     }
 
     #[test]
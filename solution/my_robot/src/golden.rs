@@ -0,0 +1,157 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use crate::analyze::read_position;
+use crate::game::*;
+use crate::reader::Reader;
+use crate::reference::{is_legal, legal_answers};
+
+// Golden positions: every piece of the engine logs in `fixtures/logs` and of the sample log of
+// the repo is played again, as the first turn of a game in the seat that answered it in the
+// log. The answer must be legal, or (0, 0) when the piece fits nowhere, and found within the
+// time limit. The positions listed in `fixtures/pinned.txt` must also get the answer written
+// there.
+
+// Per position, well below the engine timeout even in a debug build
+const TIME_LIMIT: Duration = Duration::from_secs(2);
+// map00 as player 1 against terminator
+const GAME_LOG: &str = include_str!("../../../logs/game_log.txt");
+
+struct Position {
+    log: String,
+    // Order of the piece in the log, from 1
+    number: usize,
+    seat: usize,
+    // Lines of the log from the board to the answer
+    text: String,
+}
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures")
+}
+
+// Positions of an engine log, the seat taken from the symbol of the answer. A piece without an
+// answer, at the end of a truncated log, is left out.
+fn positions(log: &str, text: &str) -> Vec<Position> {
+    let mut positions = Vec::new();
+    let mut block = String::new();
+    for line in text.lines() {
+        if Dialect::detect(line).is_some() {
+            block.clear();
+        }
+        block.push_str(line);
+        block.push('\n');
+        let seat = match line.strip_prefix("-> Answer (").and_then(|answer| answer.chars().next()) {
+            Some('@') => 1,
+            Some('$') => 2,
+            _ => continue,
+        };
+        positions.push(Position { log: log.to_string(), number: positions.len() + 1, seat, text: std::mem::take(&mut block) });
+    }
+    positions
+}
+
+// Pinned answers by log and position number, from lines `<log> <position> <x> <y>`
+fn pinned() -> HashMap<(String, usize), (i32, i32)> {
+    let text = fs::read_to_string(fixtures().join("pinned.txt")).expect("Failed to read fixtures/pinned.txt");
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [log, number, x, y] => {
+                let parse = |n: &str| n.parse::<i32>().unwrap_or_else(|_| panic!("Invalid pinned answer {:?}", line));
+                ((log.to_string(), parse(number) as usize), (parse(x), parse(y)))
+            }
+            _ => panic!("Invalid pinned answer {:?}", line),
+        })
+        .collect()
+}
+
+// What is wrong with the answer to the position, None if nothing
+fn check(position: &Position, pinned: Option<(i32, i32)>) -> Option<String> {
+    let (mut game, piece) = match read_position(&mut Reader::new(position.text.as_bytes()), Some(position.seat)) {
+        Ok(read) => read,
+        Err(err) => return Some(err),
+    };
+    let field = game.field.clone();
+    let start = Instant::now();
+    let answer = game.place_piece(&piece);
+    let elapsed = start.elapsed();
+
+    if elapsed > TIME_LIMIT {
+        return Some(format!("answered in {:?}, over the limit of {:?}", elapsed, TIME_LIMIT));
+    }
+    if !is_legal(&field, &piece, answer) && (answer != (0, 0) || !legal_answers(&field, &piece).is_empty()) {
+        return Some(format!("illegal answer {} {}", answer.0, answer.1));
+    }
+    match pinned {
+        Some(expected) if expected != answer => {
+            Some(format!("answered {} {}, pinned to {} {}", answer.0, answer.1, expected.0, expected.1))
+        }
+        _ => None,
+    }
+}
+
+#[test]
+fn test_golden_positions() {
+    let mut pinned = pinned();
+    let mut paths: Vec<PathBuf> = fs::read_dir(fixtures().join("logs"))
+        .expect("Failed to read fixtures/logs")
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    let mut logs = vec![("game_log.txt".to_string(), GAME_LOG.to_string())];
+    for path in paths {
+        logs.push((path.file_name().unwrap().to_string_lossy().into_owned(), fs::read_to_string(&path).unwrap()));
+    }
+
+    let mut checked = 0;
+    let mut failures = Vec::new();
+    for (log, text) in logs {
+        for position in positions(&log, &text) {
+            let expected = pinned.remove(&(log.clone(), position.number));
+            if let Some(failure) = check(&position, expected) {
+                failures.push(format!("{} #{} (p{}): {}", position.log, position.number, position.seat, failure));
+            }
+            checked += 1;
+        }
+    }
+    failures.extend(pinned.keys().map(|(log, number)| format!("{} #{}: pinned but not in the logs", log, number)));
+
+    assert!(checked > 0, "No golden position found");
+    assert!(failures.is_empty(), "{} of {} golden positions failed:\n{}", failures.len(), checked, failures.join("\n"));
+}
+
+#[test]
+fn test_engine_accepts_answers_off_the_board() {
+    // The robot answered negative coordinates in this log, with the padding of the piece above
+    // or left of the board
+    let log = "map01_p1_terminator.txt";
+    let text = fs::read_to_string(fixtures().join("logs").join(log)).unwrap();
+    let positions = positions(log, &text);
+
+    let mut off_the_board = 0;
+    for (position, next) in positions.iter().zip(&positions[1..]) {
+        let answer = position.text.lines().last().and_then(|line| line.split_once("): "));
+        let Some((x, y)) = answer.and_then(|(_, answer)| answer.split_once(' ')) else {
+            continue;
+        };
+        let (x, y): (i32, i32) = (x.parse().unwrap(), y.parse().unwrap());
+        if x >= 0 && y >= 0 {
+            continue;
+        }
+        let (game, piece) = read_position(&mut Reader::new(position.text.as_bytes()), Some(position.seat)).unwrap();
+        assert!(is_legal(&game.field, &piece, (x, y)), "#{}: {} {} is not legal", position.number, x, y);
+
+        // The board after the answer has the piece where the answer put it
+        let (after, _) = read_position(&mut Reader::new(next.text.as_bytes()), Some(position.seat)).unwrap();
+        for block in piece.cells.positions().filter(|block| piece.cells[block]) {
+            let cell = Pos { y: (y + block.y as i32) as usize, x: (x + block.x as i32) as usize };
+            assert_eq!(after.field.cells[&cell], Cell::Own { fresh: true }, "#{}: {} {}", position.number, x, y);
+        }
+        off_the_board += 1;
+    }
+    assert!(off_the_board > 0, "No answer off the board in {}", log);
+}
@@ -1,24 +1,157 @@
-#[derive(Debug, Clone)]
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+#[derive(Debug, Clone, PartialEq, Default)]
 pub struct Size {
     pub width: usize,
     pub height: usize,
 }
 
-pub trait Grid {
-    fn height(&self) -> usize;
-    fn width(&self) -> usize;
-    fn cells(&self) -> &Vec<Vec<char>>;
-    fn cells_mut(&mut self) -> &mut Vec<Vec<char>>;
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pos {
+    pub y: usize,
+    pub x: usize,
+}
+
+// Rectangle of cells stored row by row. `grid[y]` is row y, `grid[y][x]` or `grid[&pos]` a
+// cell, both panic outside of the grid; `get` does not.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Grid<T> {
+    size: Size,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(size: Size, value: T) -> Self {
+        let cells = vec![value; size.width * size.height];
+        Grid { size, cells }
+    }
+
+    // Gives the grid a new size filled with the value, in the storage it already has
+    pub fn reset(&mut self, size: Size, value: T) {
+        self.cells.clear();
+        self.cells.resize(size.width * size.height, value);
+        self.size = size;
+    }
+}
+
+impl<T> Grid<T> {
+    // Panics when the rows are not all as long
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Self {
+        let size = Size { width: rows.first().map_or(0, Vec::len), height: rows.len() };
+        assert!(rows.iter().all(|row| row.len() == size.width), "Rows of different lengths");
+        Grid { size, cells: rows.into_iter().flatten().collect() }
+    }
+
+    pub fn size(&self) -> &Size { &self.size }
+    pub fn width(&self) -> usize { self.size.width }
+    pub fn height(&self) -> usize { self.size.height }
 
-    fn print_grid(&self) -> String {
-        let mut output = String::new();
-        output.push_str(&format!("Grid ({} rows x {} cols):\n", self.height(), self.width()));
-        for row in self.cells() {
-            for ch in row {
-                output.push(*ch);
+    pub fn get(&self, pos: &Pos) -> Option<&T> {
+        self.contains(pos).then(|| &self.cells[pos.y * self.size.width + pos.x])
+    }
+
+    pub fn contains(&self, pos: &Pos) -> bool {
+        pos.y < self.size.height && pos.x < self.size.width
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.size.width..(y + 1) * self.size.width]
+    }
+
+    pub fn row_mut(&mut self, y: usize) -> &mut [T] {
+        let width = self.size.width;
+        &mut self.cells[y * width..(y + 1) * width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, an empty grid has no row anyway
+        self.cells.chunks(self.size.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(x).step_by(self.size.width.max(1)).take(self.size.height)
+    }
+
+    // Every position, row by row
+    pub fn positions(&self) -> impl Iterator<Item = Pos> {
+        let width = self.size.width;
+        (0..self.size.height).flat_map(move |y| (0..width).map(move |x| Pos { y, x }))
+    }
+
+    // Neighbours above, below, left and right that are inside the grid
+    pub fn neighbours4(&self, pos: &Pos) -> impl Iterator<Item = Pos> {
+        let (y, x, size) = (pos.y, pos.x, self.size.clone());
+        [(y.wrapping_sub(1), x), (y + 1, x), (y, x.wrapping_sub(1)), (y, x + 1)]
+            .into_iter()
+            .filter(move |&(ny, nx)| ny < size.height && nx < size.width)
+            .map(|(y, x)| Pos { y, x })
+    }
+
+    // Neighbours inside the grid, diagonals included
+    pub fn neighbours8(&self, pos: &Pos) -> impl Iterator<Item = Pos> {
+        let (y, x, size) = (pos.y, pos.x, self.size.clone());
+        (y.saturating_sub(1)..=(y + 1).min(size.height.saturating_sub(1)))
+            .flat_map(move |ny| (x.saturating_sub(1)..=(x + 1).min(size.width.saturating_sub(1))).map(move |nx| Pos { y: ny, x: nx }))
+            .filter(move |n| (n.y, n.x) != (y, x))
+    }
+}
+
+impl<T> Index<usize> for Grid<T> {
+    type Output = [T];
+    fn index(&self, y: usize) -> &[T] { self.row(y) }
+}
+
+impl<T> IndexMut<usize> for Grid<T> {
+    fn index_mut(&mut self, y: usize) -> &mut [T] { self.row_mut(y) }
+}
+
+impl<T> Index<&Pos> for Grid<T> {
+    type Output = T;
+    fn index(&self, pos: &Pos) -> &T { &self.row(pos.y)[pos.x] }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Grid ({} rows x {} cols):", self.height(), self.width())?;
+        for row in self.rows() {
+            for cell in row {
+                write!(f, "{}", cell)?;
             }
-            output.push('\n');
+            writeln!(f)?;
         }
-        output
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<u8> {
+        Grid::from_rows(vec![vec![0, 1, 2], vec![3, 4, 5]])
+    }
+
+    #[test]
+    fn test_access_and_slices() {
+        let mut grid = grid();
+        assert_eq!(grid[1][2], 5);
+        assert_eq!(grid[&Pos { y: 0, x: 1 }], 1);
+        assert_eq!(grid.get(&Pos { y: 2, x: 0 }), None);
+        assert_eq!(grid.get(&Pos { y: 0, x: 3 }), None);
+        assert_eq!(grid.row(1), &[3, 4, 5]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![1, 4]);
+        grid[0][0] = 9;
+        assert_eq!(grid.rows().next(), Some(&[9, 1, 2][..]));
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = grid();
+        let corner: Vec<Pos> = grid.neighbours4(&Pos { y: 0, x: 0 }).collect();
+        assert_eq!(corner, vec![Pos { y: 1, x: 0 }, Pos { y: 0, x: 1 }]);
+        assert_eq!(grid.neighbours8(&Pos { y: 0, x: 0 }).count(), 3);
+        assert_eq!(grid.neighbours8(&Pos { y: 1, x: 1 }).count(), 5);
+        assert_eq!(grid.positions().count(), 6);
     }
 }
@@ -31,4 +31,29 @@ create struct
 - Player
 - Game
 
-*/
\ No newline at end of file
+*/
+
+// The robot, and the code the baseline bots share with it: parsing of the engine input, the
+// board and its analysis, and the search of valid placements
+pub mod game;
+mod field;
+mod piece;
+mod player;
+mod grid;
+mod utils;
+mod trace;
+mod endgame;
+mod analysis;
+mod board_analysis;
+mod phase;
+mod parallel;
+mod opponent;
+mod opening;
+pub mod reader;
+mod protocol;
+pub mod analyze;
+pub mod rng;
+#[cfg(test)]
+mod golden;
+#[cfg(test)]
+mod reference;
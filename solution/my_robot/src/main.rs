@@ -1,43 +1,40 @@
-mod game;
-mod field;
-mod piece;
-mod player;
-mod grid;
-mod utils;
-
-use std::io::{self, BufRead};
-use game::*;
+use std::env;
+use std::io;
+use my_robot::analyze;
+use my_robot::game::*;
+use my_robot::reader::Reader;
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).is_some_and(|arg| arg == "analyze") {
+        analyze::main(&args);
+        return;
+    }
+
     let stdin = io::stdin();
-    let mut lines = stdin.lock().lines();
-    
-    let first_line = lines.next().unwrap().unwrap();
-    let second_line = lines.next().unwrap().unwrap();
+    let mut reader = Reader::new(stdin.lock());
 
-    let (player, enemy) = Player::new(&first_line);
-    let mut g = Game::new(
-        player, enemy,
-        Field::new(&second_line));
-    
-    g.field.update(&mut lines);
+    let (player, _) = Player::new(reader.next_line().unwrap());
+    let header = reader.next_line().unwrap();
+    let dialect = Dialect::detect(header).expect("Unknown board header");
+    let field = Field::new(header, dialect, player.symbol);
+    reader.dialect = dialect;
+    let mut g = Game::new(player, field);
 
-    loop {
-        let next_line = match lines.next() {
-            Some(Ok(line)) => line,
-            _ => break,
-        };
+    g.update_field(&mut reader);
+    g.trace = Trace::from_env(&g.player, &g.field);
 
-        if next_line.starts_with("Anfield") {
-            g.field.update(&mut lines);
-        }
-        
-        if next_line.starts_with("Piece") {
-            let mut p = Piece::new(&next_line);
-            p.update(&mut lines);
+    // Every piece is read into the same one
+    let mut p = Piece::default();
+    while let Some(next_line) = reader.next_line() {
+        if next_line.starts_with(dialect.board_keyword()) {
+            g.update_field(&mut reader);
+        } else if next_line.starts_with("Piece") {
+            p.reset(next_line, dialect);
+            p.update(&mut reader);
 
-            let (x, y) = g.place_piece(p);
-            println!("{} {}", x, y);
+            let (x, y) = g.place_piece(&p);
+            println!("{}", dialect.answer(x, y));
         }
     }
 }
@@ -0,0 +1,119 @@
+use crate::board_analysis::BoardAnalysis;
+use crate::field::{Field, Pos};
+
+// Waypoints of the best opening seen on each map, generated from the winning logs of the repo by
+// `filler-stats logs solution/my_robot/fixtures/logs --opening-book`. A line per map and pair
+// of starts:
+// width height start_y start_x enemy_start_y enemy_start_x: y,x y,x ...
+const BOOK: &str = include_str!("opening_book.txt");
+// A waypoint is reached once one of our cells is this close to it, diagonals included
+const REACHED_DISTANCE: usize = 2;
+// Our turns after which the book is given up, the game went another way
+const MAX_BOOK_TURNS: usize = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Opening {
+    pub waypoints: Vec<Pos>,
+}
+
+impl Opening {
+    // Opening of the book for the board of our first turn, matched by its size, our start cell
+    // and the enemy start cell. When we are player 2 the enemy already placed a piece over its
+    // start, so its start only has to be one of the enemy cells.
+    pub fn find(field: &Field) -> Option<Self> {
+        let start = single_player_cell(field)?;
+        BOOK.lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(parse_line)
+            .find(|(width, height, book_start, enemy_start, _)| {
+                (*width, *height) == (field.width(), field.height())
+                    && *book_start == start
+                    && field.cells.get(&Pos { y: enemy_start.0, x: enemy_start.1 }).is_some_and(|cell| cell.is_enemy())
+            })
+            .map(|(_, _, _, _, waypoints)| Opening { waypoints })
+    }
+
+    // First waypoint that is still empty and that none of our cells is close to, None once the
+    // book is played out
+    pub fn target(&self, field: &Field, analysis: &BoardAnalysis, turn: usize) -> Option<Pos> {
+        if turn > MAX_BOOK_TURNS {
+            return None;
+        }
+        self.waypoints.iter()
+            .find(|waypoint| field.cells[*waypoint].is_empty() && analysis.player_distance(waypoint) > REACHED_DISTANCE)
+            .cloned()
+    }
+}
+
+fn single_player_cell(field: &Field) -> Option<(usize, usize)> {
+    let mut cells = field.cells.positions().filter(|pos| field.cells[pos].is_own());
+    match (cells.next(), cells.next()) {
+        (Some(cell), None) => Some((cell.y, cell.x)),
+        _ => None,
+    }
+}
+
+type BookLine = (usize, usize, (usize, usize), (usize, usize), Vec<Pos>);
+
+fn parse_line(line: &str) -> Option<BookLine> {
+    let (header, waypoints) = line.split_once(':')?;
+    let numbers: Vec<usize> = header.split_whitespace().map(|n| n.parse().ok()).collect::<Option<_>>()?;
+    let [width, height, start_y, start_x, enemy_y, enemy_x] = numbers[..] else {
+        return None;
+    };
+    let waypoints = waypoints
+        .split_whitespace()
+        .map(|waypoint| {
+            let (y, x) = waypoint.split_once(',')?;
+            let pos = Pos { y: y.parse().ok()?, x: x.parse().ok()? };
+            (pos.y < height && pos.x < width).then_some(pos)
+        })
+        .collect::<Option<Vec<Pos>>>()?;
+    Some((width, height, (start_y, start_x), (enemy_y, enemy_x), waypoints))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_book_lines_are_valid() {
+        let lines: Vec<&str> = BOOK.lines().filter(|line| !line.starts_with('#')).collect();
+        assert!(!lines.is_empty());
+        for line in lines {
+            assert!(parse_line(line).is_some_and(|(.., waypoints)| !waypoints.is_empty()), "{}", line);
+        }
+    }
+
+    #[test]
+    fn test_logged_wins_are_in_the_book() {
+        // map00 as player 1
+        let mut rows = vec!["...................."; 15];
+        rows[2] = ".........@..........";
+        rows[12] = ".........$..........";
+        let map00 = Field::from_rows(&rows, ('a', '@'));
+        let first = Opening::find(&map00).unwrap();
+        assert!(first.target(&map00, &BoardAnalysis::new(&map00), 1).is_some());
+        // map01 as player 2, the enemy has already placed its first piece
+        let mut rows = vec!["........................................"; 30];
+        rows[3] = "...@@...................................";
+        rows[26] = "................................$.......";
+        let map01 = Field::from_rows(&rows, ('s', '$'));
+        assert!(Opening::find(&map01).is_some());
+        // Some other map
+        assert!(Opening::find(&Field::from_rows(&["@...", "...$"], ('a', '@'))).is_none());
+    }
+
+    #[test]
+    fn test_target_skips_reached_waypoints() {
+        let field = Field::from_rows(&[
+            "@.........",
+            "..........",
+            ".........$",
+        ], ('a', '@'));
+        let analysis = BoardAnalysis::new(&field);
+        let opening = Opening { waypoints: vec![Pos { y: 1, x: 1 }, Pos { y: 1, x: 6 }] };
+        assert_eq!(opening.target(&field, &analysis, 1), Some(Pos { y: 1, x: 6 }));
+        assert_eq!(opening.target(&field, &analysis, MAX_BOOK_TURNS + 1), None);
+    }
+}
@@ -0,0 +1,255 @@
+use crate::board_analysis::BoardAnalysis;
+use crate::field::{Cell, Field, Pos};
+use crate::phase::{Phase, Weights};
+
+// The engine does not tell who the enemy is, so its style is read from its placements: the
+// enemy cells that appeared in the last update of the board.
+
+// Placements observed before the style is trusted
+const MIN_PLACEMENTS: usize = 8;
+// Share of placements touching our cells above which the enemy hugs our border
+const HUG_RATE: f32 = 0.2;
+// Share of placements landing within CLOSE_DISTANCE cells of ours above which it is aggressive
+const CLOSE_RATE: f32 = 0.4;
+const CLOSE_DISTANCE: usize = 2;
+// Share of placements growing towards our start above which the enemy races us
+const TOWARD_RATE: f32 = 0.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    // Not enough placements seen yet
+    Unknown,
+    // Sticks to our border and fights for every cell, like terminator
+    Hugger,
+    // Grows towards us without fighting at the border
+    Racer,
+    // Grows away from us, filling its own side
+    Expander,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Profile {
+    // Whether the starting positions are known
+    started: bool,
+    // Centers of both starting positions, as (y, x)
+    enemy_start: (f32, f32),
+    player_start: (f32, f32),
+    placements: usize,
+    hugs: usize,
+    close: usize,
+    toward: usize,
+}
+
+impl Profile {
+    // Records the enemy placement of the last update, the first call only records the starting
+    // positions
+    pub fn observe(&mut self, field: &Field, analysis: &BoardAnalysis) {
+        let new_cells = analysis.new_enemy_cells();
+        if !self.started {
+            // As player 2 the first board already holds the first enemy piece, fresh, next to
+            // the start
+            let start: Vec<Pos> = new_cells.iter().filter(|&cell| field.cells[cell] == Cell::Enemy { fresh: false }).cloned().collect();
+            self.enemy_start = center(&start);
+            self.player_start = center(analysis.new_player_cells());
+            self.started = true;
+            return;
+        }
+        if new_cells.is_empty() {
+            return;
+        }
+
+        self.placements += 1;
+        let touches = new_cells.iter().any(|cell| analysis.touches_player(cell));
+        let close = new_cells.iter().any(|cell| analysis.player_distance(cell) <= CLOSE_DISTANCE);
+        self.hugs += touches as usize;
+        self.close += close as usize;
+
+        // Growing towards us when the placement is within 60 degrees of the direction of our start
+        let (cy, cx) = center(new_cells);
+        let growth = (cy - self.enemy_start.0, cx - self.enemy_start.1);
+        let to_player = (self.player_start.0 - self.enemy_start.0, self.player_start.1 - self.enemy_start.1);
+        let lengths = growth.0.hypot(growth.1) * to_player.0.hypot(to_player.1);
+        if lengths > 0.0 && (growth.0 * to_player.0 + growth.1 * to_player.1) / lengths > 0.5 {
+            self.toward += 1;
+        }
+    }
+
+    pub fn style(&self) -> Style {
+        if self.placements < MIN_PLACEMENTS {
+            return Style::Unknown;
+        }
+        let rate = |count: usize| count as f32 / self.placements as f32;
+        if rate(self.hugs) >= HUG_RATE && rate(self.close) >= CLOSE_RATE {
+            Style::Hugger
+        } else if rate(self.toward) >= TOWARD_RATE {
+            Style::Racer
+        } else {
+            Style::Expander
+        }
+    }
+}
+
+impl Style {
+    // Evaluator weights of the phase, adjusted to counter the enemy while it can still reach us
+    pub fn counter_weights(self, phase: Phase) -> Weights {
+        let mut weights = phase.weights();
+        if !matches!(phase, Phase::Opening | Phase::Fight) {
+            return weights;
+        }
+        match self {
+            Style::Unknown => {}
+            // It contests every cell at the border, sealing corridors behind the border does not
+            // pay off, blocking its placements does
+            Style::Hugger => {
+                weights.choke_point = 0;
+                weights.enemy_mobility *= 2;
+            }
+            // It comes to us, before and after the contact: wall it off where it arrives by
+            // blocking its placements and sealing the corridors it comes through
+            Style::Racer => {
+                weights.enemy_mobility *= 3;
+                weights.choke_point *= 3;
+            }
+            // It leaves room in front of us, take it and cut its side off
+            Style::Expander => {
+                weights.enemy_distance *= 2;
+                weights.choke_point *= 2;
+            }
+        }
+        weights
+    }
+}
+
+fn center(cells: &[Pos]) -> (f32, f32) {
+    let count = cells.len().max(1) as f32;
+    let (y, x) = cells.iter().fold((0, 0), |(y, x), cell| (y + cell.y, x + cell.x));
+    (y as f32 / count, x as f32 / count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::{evaluate_placements, Game, Piece, Player};
+
+    fn field(rows: &[String]) -> Field {
+        let rows: Vec<&str> = rows.iter().map(String::as_str).collect();
+        Field::from_rows(&rows, ('a', '@'))
+    }
+
+    // Takes the cell for the enemy, as an update of the board would
+    fn take(analysis: &mut BoardAnalysis, rows: &mut [String], y: usize, x: usize) {
+        rows[y].replace_range(x..=x, "s");
+        analysis.update(&field(rows), &[Pos { y, x }]);
+    }
+
+    #[test]
+    fn test_enemy_hugging_our_border() {
+        let mut profile = Profile::default();
+        let mut rows: Vec<String> = vec![
+            "@@@@@@@@@@".to_string(),
+            "..........".to_string(),
+            "..........".to_string(),
+            "$.........".to_string(),
+        ];
+        let mut analysis = BoardAnalysis::new(&field(&rows));
+        profile.observe(&field(&rows), &analysis);
+        // The enemy climbs to our border, then runs along it
+        for (y, x) in [(2, 0), (1, 0), (1, 1), (1, 2), (1, 3), (1, 4), (1, 5), (1, 6)] {
+            assert_eq!(profile.style(), Style::Unknown);
+            take(&mut analysis, &mut rows, y, x);
+            profile.observe(&field(&rows), &analysis);
+        }
+        assert_eq!(profile.style(), Style::Hugger);
+        assert_eq!(Style::Hugger.counter_weights(Phase::Fight).choke_point, 0);
+        assert_eq!(Style::Hugger.counter_weights(Phase::Fill), Phase::Fill.weights());
+    }
+
+    #[test]
+    fn test_enemy_growing_away() {
+        let mut profile = Profile::default();
+        let mut rows: Vec<String> = vec![
+            "@.............".to_string(),
+            "..............".to_string(),
+            "..............".to_string(),
+            ".....$........".to_string(),
+        ];
+        let mut analysis = BoardAnalysis::new(&field(&rows));
+        profile.observe(&field(&rows), &analysis);
+        // A placement without new cells is not one
+        analysis.update(&field(&rows), &[]);
+        profile.observe(&field(&rows), &analysis);
+        assert_eq!(profile.placements, 0);
+        // The enemy runs along the bottom side, away from our corner
+        for x in 6..14 {
+            take(&mut analysis, &mut rows, 3, x);
+            profile.observe(&field(&rows), &analysis);
+        }
+        assert_eq!(profile.style(), Style::Expander);
+    }
+
+    #[test]
+    fn test_enemy_start_as_player_2() {
+        // Player 1 has placed its first piece, fresh, next to its start
+        let rows = ["@.....".to_string(), "......".to_string(), "..ss..".to_string(), "...$s.".to_string()];
+        let mut profile = Profile::default();
+        profile.observe(&field(&rows), &BoardAnalysis::new(&field(&rows)));
+        assert_eq!((profile.enemy_start, profile.player_start), ((3.0, 3.0), (0.0, 0.0)));
+    }
+
+    // Answer chosen against the style on the board, for the phase the board is in
+    fn answer(style: Style, rows: &[&str], piece: &[&str]) -> (i32, i32) {
+        let player = Player { _num: 1, symbol: ('a', '@'), score: 0 };
+        let game = Game::new(player, Field::from_rows(rows, ('a', '@')));
+        let piece = Piece::from_rows(piece);
+        let mut placements = game.valid_placements(&piece);
+        let weights = style.counter_weights(Phase::detect(&game.analysis, !placements.is_empty()));
+        evaluate_placements(&game.field, &game.analysis, &mut placements, None, &weights, &[]).answer()
+    }
+
+    #[test]
+    fn test_counter_changes_the_move() {
+        // The enemy comes down from our corner
+        let coming = [
+            "@@@.......",
+            "@.........",
+            "..$.......",
+            ".$$.......",
+            ".$$$......",
+            "$$$$......",
+            "..........",
+            "..........",
+        ];
+        assert_eq!(answer(Style::Unknown, &coming, &["O", "O"]), (0, 1));
+        // Blocking it beats racing it
+        assert_eq!(answer(Style::Hugger, &coming, &["O", "O"]), (2, 0));
+        assert_eq!(answer(Style::Racer, &coming, &["O", "O"]), (2, 0));
+
+        // The enemy has reached us and goes on down the left side
+        let fight = [
+            "........",
+            "$@......",
+            "$@$$$$..",
+            ".@@..$$.",
+            "..@@@.$.",
+            "....@@@.",
+        ];
+        assert_eq!(answer(Style::Unknown, &fight, &["OO"]), (1, 1));
+        // Sealing its way down beats growing next to it
+        assert_eq!(answer(Style::Racer, &fight, &["OO"]), (0, 3));
+
+        // The enemy stays in its corner
+        let away = [
+            "..........",
+            "..........",
+            "..........",
+            "..........",
+            "...@@@....",
+            "...@@@.$$.",
+            ".......$$.",
+            ".......$..",
+        ];
+        assert_eq!(answer(Style::Unknown, &away, &["OO", "O."]), (5, 5));
+        // Taking the room in front of it beats closing in
+        assert_eq!(answer(Style::Expander, &away, &["OO", "O."]), (5, 3));
+    }
+}
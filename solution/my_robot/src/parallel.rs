@@ -0,0 +1,246 @@
+use std::collections::VecDeque;
+use std::env;
+use std::mem;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc::{self, Receiver};
+use std::sync::{Condvar, Mutex, MutexGuard, Once, OnceLock, PoisonError};
+use std::thread;
+
+// Set to a number of threads to override the number of cores, 1 runs everything on the main thread
+pub const THREADS_ENV: &str = "FILLER_THREADS";
+// Fewest items worth a thread of their own, handing a chunk to a worker costs more than scoring
+// a few placements
+const MIN_CHUNK_LEN: usize = 64;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+// Worker threads, started on the first call and kept for the whole game: the evaluators of a
+// turn only send them jobs instead of each starting threads under the time limit of the engine
+#[derive(Default)]
+struct Pool {
+    queue: Mutex<VecDeque<Job>>,
+    ready: Condvar,
+}
+
+// The pool, with one worker less than `threads()` as the calling thread runs a chunk too
+fn pool() -> &'static Pool {
+    static POOL: OnceLock<Pool> = OnceLock::new();
+    static WORKERS: Once = Once::new();
+    let pool = POOL.get_or_init(Pool::default);
+    WORKERS.call_once(|| {
+        for _ in 1..threads() {
+            thread::spawn(|| pool.work());
+        }
+    });
+    pool
+}
+
+impl Pool {
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Job>> {
+        self.queue.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn work(&self) {
+        loop {
+            let mut queue = self.lock();
+            let job = loop {
+                match queue.pop_front() {
+                    Some(job) => break job,
+                    None => queue = self.ready.wait(queue).unwrap_or_else(PoisonError::into_inner),
+                }
+            };
+            drop(queue);
+            job();
+        }
+    }
+
+    // Runs the tasks, the first one on the calling thread and the others on the workers, and
+    // returns their results in the order of the tasks. A panic of a task is raised again here
+    // once every task is over.
+    fn run<'a, R, T>(&self, tasks: Vec<T>) -> Vec<R>
+    where
+        R: Send + 'a,
+        T: FnOnce() -> R + Send + 'a,
+    {
+        let mut results: Vec<Option<thread::Result<R>>> = (0..tasks.len()).map(|_| None).collect();
+        let (done, finished) = mpsc::channel();
+        let mut tasks = tasks.into_iter().enumerate();
+        let first = tasks.next();
+
+        let mut sent = Sent { pool: self, finished, left: 0 };
+        for (i, task) in tasks {
+            let done = done.clone();
+            let job: Box<dyn FnOnce() + Send + 'a> = Box::new(move || {
+                let _ = done.send((i, panic::catch_unwind(AssertUnwindSafe(task))));
+            });
+            // SAFETY: the job may borrow data that only lives for 'a. `sent` does not let this
+            // call return, nor unwind, before every job it counts has run, so none outlives 'a.
+            let job = unsafe { mem::transmute::<Box<dyn FnOnce() + Send + 'a>, Job>(job) };
+            self.lock().push_back(job);
+            self.ready.notify_one();
+            sent.left += 1;
+        }
+        drop(done);
+
+        if let Some((i, task)) = first {
+            results[i] = Some(panic::catch_unwind(AssertUnwindSafe(task)));
+        }
+        while let Some((i, result)) = sent.next() {
+            results[i] = Some(result);
+        }
+        results
+            .into_iter()
+            .map(|result| match result.expect("task without a result") {
+                Ok(result) => result,
+                Err(panic) => panic::resume_unwind(panic),
+            })
+            .collect()
+    }
+}
+
+// Jobs sent to the workers that have not finished. Dropping it waits for them, so that no job
+// still borrows the data of a call once the call is over.
+struct Sent<'p, R> {
+    pool: &'p Pool,
+    finished: Receiver<(usize, thread::Result<R>)>,
+    left: usize,
+}
+
+impl<R> Sent<'_, R> {
+    // Result of the next job to finish, None once all have. While jobs wait in the queue this
+    // thread runs them too, so that they finish even without a free worker.
+    fn next(&mut self) -> Option<(usize, thread::Result<R>)> {
+        while self.left > 0 {
+            let result = match self.finished.try_recv() {
+                Ok(result) => result,
+                Err(_) => {
+                    let queued = self.pool.lock().pop_front();
+                    match queued {
+                        Some(job) => {
+                            job();
+                            continue;
+                        }
+                        // Every job has been taken by a worker
+                        None => self.finished.recv().ok()?,
+                    }
+                }
+            };
+            self.left -= 1;
+            return Some(result);
+        }
+        None
+    }
+}
+
+impl<R> Drop for Sent<'_, R> {
+    fn drop(&mut self) {
+        while self.next().is_some() {}
+    }
+}
+
+// Number of threads used to split work, read once
+pub fn threads() -> usize {
+    static THREADS: OnceLock<usize> = OnceLock::new();
+    *THREADS.get_or_init(|| {
+        env::var(THREADS_ENV)
+            .ok()
+            .and_then(|threads| threads.parse().ok())
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()))
+            .max(1)
+    })
+}
+
+// Runs `f` on consecutive chunks of `items`, one chunk per thread of the pool, and returns the
+// results in the order of the chunks. Every item is in exactly one chunk and chunks never depend
+// on thread scheduling, so as long as `f` only touches its chunk the outcome is the same as
+// running it on the whole slice.
+pub fn for_each_chunk<T, R, F>(items: &mut [T], f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(&mut [T]) -> R + Sync,
+{
+    for_each_chunk_on(threads(), items, f)
+}
+
+// Maps every item to a result, in the order of `items`
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    map_on(threads(), items, f)
+}
+
+fn map_on<T, R, F>(threads: usize, items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let chunk_len = chunk_len(threads, items.len());
+    if chunk_len >= items.len() {
+        return items.iter().map(f).collect();
+    }
+    let f = &f;
+    let tasks: Vec<_> = items.chunks(chunk_len).map(|chunk| move || chunk.iter().map(f).collect::<Vec<R>>()).collect();
+    pool().run(tasks).into_iter().flatten().collect()
+}
+
+fn for_each_chunk_on<T, R, F>(threads: usize, items: &mut [T], f: F) -> Vec<R>
+where
+    T: Send,
+    R: Send,
+    F: Fn(&mut [T]) -> R + Sync,
+{
+    let chunk_len = chunk_len(threads, items.len());
+    if chunk_len >= items.len() {
+        return vec![f(items)];
+    }
+    let f = &f;
+    let tasks: Vec<_> = items.chunks_mut(chunk_len).map(|chunk| move || f(chunk)).collect();
+    pool().run(tasks)
+}
+
+// Items per chunk so that there are at most `threads` chunks of at least MIN_CHUNK_LEN items
+fn chunk_len(threads: usize, len: usize) -> usize {
+    len.div_ceil(threads).max(MIN_CHUNK_LEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunks_are_merged_in_order() {
+        let mut items: Vec<usize> = (0..1000).collect();
+        let sums = for_each_chunk_on(4, &mut items, |chunk| {
+            chunk.iter_mut().for_each(|item| *item *= 2);
+            chunk.len()
+        });
+        assert_eq!(sums, vec![250; 4]);
+        assert_eq!(items, (0..1000).map(|item| item * 2).collect::<Vec<usize>>());
+
+        // Too few items to be worth four threads
+        let mut items: Vec<usize> = (0..100).collect();
+        assert_eq!(for_each_chunk_on(4, &mut items, |chunk| chunk.len()), vec![64, 36]);
+        let mut items: Vec<usize> = (0..50).collect();
+        assert_eq!(for_each_chunk_on(4, &mut items, |chunk| chunk.len()), vec![50]);
+    }
+
+    #[test]
+    fn test_map_keeps_order() {
+        let items: Vec<usize> = (0..1000).collect();
+        assert_eq!(map_on(4, &items, |item| item + 1), (1..1001).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_panic_of_a_chunk_reaches_the_caller() {
+        let items: Vec<usize> = (0..1000).collect();
+        let result = panic::catch_unwind(|| map_on(4, &items, |&item| if item == 900 { panic!("chunk failed") } else { item }));
+        assert!(result.is_err());
+        // The workers are still there for the next call
+        assert_eq!(map_on(4, &items, |item| item + 1), (1..1001).collect::<Vec<usize>>());
+    }
+}
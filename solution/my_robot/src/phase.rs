@@ -0,0 +1,120 @@
+use crate::board_analysis::BoardAnalysis;
+
+// Stage of the game, read from the board rather than the turn count so that it means the
+// same on every map size: turn 30 ends a game on map00 but is still the opening on map02.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    // Our cells do not touch the enemy yet, race towards it
+    Opening,
+    // Our cells touch the enemy, block it and cut it off
+    Fight,
+    // No empty cell we can reach is next to the enemy, pack our region
+    Fill,
+    // The piece fits nowhere
+    Stuck,
+}
+
+// Score of each evaluator in a phase, 0 leaves the evaluator out
+#[derive(Debug, Clone, PartialEq)]
+pub struct Weights {
+    // Score of the placement closest to the enemy, halved for each next one
+    pub enemy_distance: i32,
+    // Score of each enemy placement blocked
+    pub enemy_mobility: i32,
+    // Score of each empty cell sealed off from the enemy
+    pub choke_point: i32,
+    // Score of each cell right next to an enemy cell, divided by the distance up to 3 cells
+    pub enclosing: i32,
+    // Score of a placement leaving no hole in its bounding box
+    pub perfect_fit: i32,
+    // Exact packing search of our region, when it is small enough
+    pub endgame: bool,
+}
+
+impl Phase {
+    // `has_placements` tells whether the current piece fits anywhere
+    pub fn detect(analysis: &BoardAnalysis, has_placements: bool) -> Phase {
+        if !has_placements {
+            Phase::Stuck
+        } else if analysis.is_sealed_off() {
+            Phase::Fill
+        } else if analysis.is_touching_enemy() {
+            Phase::Fight
+        } else {
+            Phase::Opening
+        }
+    }
+
+    pub fn weights(self) -> Weights {
+        match self {
+            Phase::Opening => Weights {
+                enemy_distance: 36,
+                enemy_mobility: 3,
+                choke_point: 1,
+                enclosing: 12,
+                perfect_fit: 1,
+                endgame: false,
+            },
+            Phase::Fight => Weights {
+                enemy_distance: 0,
+                enemy_mobility: 3,
+                choke_point: 1,
+                enclosing: 12,
+                perfect_fit: 5,
+                endgame: false,
+            },
+            Phase::Fill => Weights {
+                enemy_distance: 0,
+                enemy_mobility: 0,
+                choke_point: 0,
+                enclosing: 0,
+                perfect_fit: 50,
+                endgame: true,
+            },
+            Phase::Stuck => Weights {
+                enemy_distance: 0,
+                enemy_mobility: 0,
+                choke_point: 0,
+                enclosing: 0,
+                perfect_fit: 0,
+                endgame: false,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::field::Field;
+
+    fn analysis(rows: &[&str]) -> BoardAnalysis {
+        BoardAnalysis::new(&Field::from_rows(rows, ('a', '@')))
+    }
+
+    #[test]
+    fn test_detect_phase() {
+        let opening = analysis(&[
+            "@.....",
+            "......",
+            ".....$",
+        ]);
+        assert_eq!(Phase::detect(&opening, true), Phase::Opening);
+        assert_eq!(Phase::detect(&opening, false), Phase::Stuck);
+
+        let fight = analysis(&[
+            "@@@...",
+            "..@$$.",
+            "......",
+        ]);
+        assert_eq!(Phase::detect(&fight, true), Phase::Fight);
+
+        // Touching the enemy, but every empty cell left to us is behind our wall
+        let fill = analysis(&[
+            "..@$$.",
+            "@@@$..",
+            "$$$$..",
+        ]);
+        assert_eq!(Phase::detect(&fill, true), Phase::Fill);
+    }
+}
@@ -1,40 +1,55 @@
 use crate::grid::{Grid, Size};
-use std::io::Error;
+use crate::protocol::Dialect;
+use crate::reader::Reader;
+use std::fmt;
+use std::io::BufRead;
 
-#[derive(Debug, Clone)]
+// Cells of a piece are true where the piece has a block
+#[derive(Debug, Clone, Default)]
 pub struct Piece {
     pub size: Size,
-    pub cells: Vec<Vec<char>>,
+    pub cells: Grid<bool>,
     pub trimmed_size: Size,
-    pub trimmed_cells: Vec<Vec<char>>,
+    pub trimmed_cells: Grid<bool>,
     pub symbol_count: usize,
     pub offset: (usize, usize),
 }
 
 impl Piece {
     // Create a new Piece instance with given dimensions
-    pub fn new(header: &str) -> Self {
-        let parts: Vec<&str> = header.trim_end_matches(':').split_whitespace().collect();
-        let col_count: usize = parts[1].parse().expect("Invalid col count");
-        let row_count: usize = parts[2].parse().expect("Invalid row count");
-
-        let cells = vec![vec!['.'; col_count]; row_count];
-
-        Piece {
-            size: Size {
-                width: col_count,
-                height: row_count,
-            },
-            cells,
-            trimmed_size: Size {
-                width: 0,
-                height: 0,
-            },
-            trimmed_cells: vec![],
-            symbol_count: 0,
+    pub fn new(header: &str, dialect: Dialect) -> Self {
+        let mut piece = Piece::default();
+        piece.reset(header, dialect);
+        piece
+    }
+
+    // Empties the piece for the next one of the given header, keeping the storage of its grids
+    // so that reading a piece every turn does not allocate
+    pub fn reset(&mut self, header: &str, dialect: Dialect) {
+        self.size = dialect.size(header);
+        self.cells.reset(self.size.clone(), false);
+        self.symbol_count = 0;
+    }
+
+    // Piece drawn with `O` and `.`, one string per row
+    #[cfg(test)]
+    pub fn from_rows(rows: &[&str]) -> Self {
+        let cells: Vec<Vec<bool>> = rows.iter().map(|row| row.chars().map(|c| c == 'O').collect()).collect();
+        let mut piece = Piece {
+            size: Size { width: rows[0].len(), height: rows.len() },
+            symbol_count: cells.iter().flatten().filter(|&&block| block).count(),
+            cells: Grid::from_rows(cells),
+            trimmed_size: Size { width: 0, height: 0 },
+            trimmed_cells: Grid::from_rows(vec![]),
             offset: (0, 0),
-        }
+        };
+        piece.trim_cells();
+        piece
     }
+
+    pub fn height(&self) -> usize { self.size.height }
+    pub fn width(&self) -> usize { self.size.width }
+
     // Trim empty rows and columns from the piece
     fn trim_cells(&mut self) {
         let mut top = 0;
@@ -42,81 +57,65 @@ impl Piece {
         let mut left = 0;
         let mut right = self.width();
 
-        while top < bottom && self.cells[top].iter().all(|&c| c == '.') {
+        while top < bottom && !self.cells[top].contains(&true) {
             top += 1
         }
-        while bottom > top && self.cells[bottom - 1].iter().all(|&c| c == '.') {
+        while bottom > top && !self.cells[bottom - 1].contains(&true) {
             bottom -= 1
         }
-        while left < right && (top..bottom).all(|i| self.cells[i][left] == '.') {
+        while left < right && !self.cells.column(left).any(|&block| block) {
             left += 1
         }
-        while right > left && (top..bottom).all(|i| self.cells[i][right - 1] == '.') {
+        while right > left && !self.cells.column(right - 1).any(|&block| block) {
             right -= 1
         }
 
-        let mut trimmed = vec![];
-        (top..bottom).for_each(|i| trimmed.push(self.cells[i][left..right].to_vec()));
-
-        self.trimmed_cells = trimmed;
-        self.trimmed_size = Size {
-            width: self.trimmed_cells[0].len(),
-            height: self.trimmed_cells.len(),
-        };
+        self.trimmed_cells.reset(Size { width: right - left, height: bottom - top }, false);
+        for y in top..bottom {
+            self.trimmed_cells[y - top].copy_from_slice(&self.cells[y][left..right]);
+        }
+        self.trimmed_size = self.trimmed_cells.size().clone();
         self.offset = (top, left);
     }
-    // Update the piece's cells from input lines
-    pub fn update<I: Iterator<Item = Result<String, Error>>>(&mut self, lines: &mut I) {
+
+    // Update the piece's cells from input lines, straight into the cells
+    pub fn update<R: BufRead>(&mut self, reader: &mut Reader<R>) {
+        let width = self.width();
+        let dialect = reader.dialect;
         for r in 0..self.height() {
-            let line = match lines.next() {
-                Some(Ok(l)) => l.trim_end().to_string(),
-                _ => panic!("Unexpected end of input while reading row {}", r),
+            let line = match reader.next_line() {
+                Some(line) => line.trim_end(),
+                None => panic!("Unexpected end of input while reading row {}", r),
             };
 
-            if line.chars().count() < self.width() {
+            if line.chars().count() < width {
                 panic!(
                     "Invalid row {}: expected at least {} characters, got {}",
                     r,
-                    self.width(),
+                    width,
                     line.chars().count()
                 );
             }
-
-            let row_data: Vec<char> = line.chars().take(self.width()).collect();
-            if row_data.len() != self.width() {
-                panic!(
-                    "Row {} has incorrect number of columns: expected {}, got {}",
-                    r,
-                    self.width(),
-                    row_data.len()
-                );
+            for (cell, c) in self.cells.row_mut(r).iter_mut().zip(line.chars()) {
+                *cell = match dialect.piece_cell(c) {
+                    'O' => true,
+                    '.' => false,
+                    _ => panic!("Invalid piece cell {:?} in row {}", c, r),
+                };
             }
-            self.symbol_count += row_data.iter().filter(|&&ch| ch != '.').count();
-            self.cells[r] = row_data;
+            self.symbol_count += self.cells[r].iter().filter(|&&block| block).count();
         }
         self.trim_cells();
     }
 }
 
-impl Grid for Piece {
-    fn height(&self) -> usize {
-        self.size.height
-    }
-    fn width(&self) -> usize {
-        self.size.width
-    }
-    fn cells(&self) -> &Vec<Vec<char>> {
-        &self.cells
-    }
-    fn cells_mut(&mut self) -> &mut Vec<Vec<char>> {
-        &mut self.cells
-    }
-}
-use std::fmt;
-
 impl fmt::Display for Piece {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.print_grid())
+        for row in self.cells.rows() {
+            let line: String = row.iter().map(|&block| if block { 'O' } else { '.' }).collect();
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
     }
 }
 
@@ -127,60 +126,43 @@ mod tests {
     #[test]
     fn test_trim_cells_with_padding() {
         // Create a piece with padding on all sides
-        let mut piece = Piece {
-            size: Size {
-                width: 5,
-                height: 5,
-            },
-            cells: vec![
-                vec!['.', '.', '.', '.', '.'], // Empty row (top)
-                vec!['.', 'O', 'O', '.', '.'], // Content row
-                vec!['.', 'O', '.', '.', '.'], // Content row
-                vec!['.', '.', '.', '.', '.'], // Empty row (bottom)
-                vec!['.', '.', '.', '.', '.'], // Empty row (bottom)
-            ],
-            trimmed_size: Size {
-                width: 0,
-                height: 0,
-            },
-            trimmed_cells: vec![],
-            symbol_count: 0,
-            offset: (0, 0),
-        };
-
-        piece.trim_cells();
+        let piece = Piece::from_rows(&[
+            ".....", // Empty row (top)
+            ".OO..", // Content row
+            ".O...", // Content row
+            ".....", // Empty row (bottom)
+            ".....", // Empty row (bottom)
+        ]);
 
         // Should trim to just the 2x2 content area
         assert_eq!(piece.trimmed_size.width, 2);
         assert_eq!(piece.trimmed_size.height, 2);
         assert_eq!(piece.offset, (1, 1)); // Offset by 1 row and 1 column
-        assert_eq!(piece.trimmed_cells, vec![vec!['O', 'O'], vec!['O', '.'],]);
+        assert_eq!(piece.trimmed_cells, Grid::from_rows(vec![vec![true, true], vec![true, false]]));
     }
 
     #[test]
     fn test_trim_cells_no_trimming_needed() {
         // Create a piece with no empty edges
-        let mut piece = Piece {
-            size: Size {
-                width: 2,
-                height: 2,
-            },
-            cells: vec![vec!['O', '.'], vec!['.', 'O']],
-            trimmed_size: Size {
-                width: 0,
-                height: 0,
-            },
-            trimmed_cells: vec![],
-            symbol_count: 0,
-            offset: (0, 0),
-        };
-
-        piece.trim_cells();
+        let piece = Piece::from_rows(&["O.", ".O"]);
 
         // Should remain the same size since no trimming needed
         assert_eq!(piece.trimmed_size.width, 2);
         assert_eq!(piece.trimmed_size.height, 2);
         assert_eq!(piece.offset, (0, 0)); // No offset
-        assert_eq!(piece.trimmed_cells, vec![vec!['O', '.'], vec!['.', 'O'],]);
+        assert_eq!(piece.trimmed_cells, Grid::from_rows(vec![vec![true, false], vec![false, true]]));
+    }
+
+    #[test]
+    fn test_read_a_smaller_piece_into_the_same_one() {
+        let mut reader = Reader::new("..O.\n.OO.\nOO..\n.O\n".as_bytes());
+        let mut piece = Piece::new("Piece 4 3:", Dialect::Anfield);
+        piece.update(&mut reader);
+        assert_eq!((piece.trimmed_size.clone(), piece.symbol_count), (Size { width: 3, height: 3 }, 5));
+
+        piece.reset("Piece 2 1:", Dialect::Anfield);
+        piece.update(&mut reader);
+        assert_eq!((piece.size.clone(), piece.symbol_count, piece.offset), (Size { width: 2, height: 1 }, 1, (0, 1)));
+        assert_eq!(piece.trimmed_cells, Grid::from_rows(vec![vec![true]]));
     }
 }
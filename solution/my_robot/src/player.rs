@@ -24,10 +24,6 @@ impl Player {
             (p2, p1)
         }
     }
-    // instance method - checks if a character belongs to this player
-    pub fn is_mine(&self, c: &char) -> bool {
-        self.symbol.0 == *c || self.symbol.1 == *c
-    }
 }
 
 #[cfg(test)]
@@ -50,24 +46,4 @@ mod tests {
         assert_eq!(enemy._num, 1);
         assert_eq!(enemy.symbol, ('a', '@'));
     }
-
-    #[test]
-    fn test_is_mine() {
-        let p1 = Player {
-            _num: 1,
-            symbol: ('a', '@'),
-            score: 0,
-        };
-
-        // Test player's own symbols
-        assert_eq!(p1.is_mine(&'a'), true);
-        assert_eq!(p1.is_mine(&'@'), true);
-
-        // Test enemy symbols
-        assert_eq!(p1.is_mine(&'s'), false);
-        assert_eq!(p1.is_mine(&'$'), false);
-
-        // Test empty cell
-        assert_eq!(p1.is_mine(&'.'), false);
-    }
 }
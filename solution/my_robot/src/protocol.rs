@@ -0,0 +1,102 @@
+use crate::grid::Size;
+
+// The two dialects of the game engine protocol. They are normalized into the 01 edu one as
+// they are read, so the rest of the robot only knows `@`/`a` and `$`/`s` owners and `O` piece
+// cells, and answers `X Y`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    // 01 edu engine: `Anfield width height:` and `Piece width height:`, `@`/`a` for player 1,
+    // `$`/`s` for player 2, `O` piece cells, answers `X Y`
+    #[default]
+    Anfield,
+    // Classic 42 engine: `Plateau height width:` and `Piece height width:`, `O`/`o` for
+    // player 1, `X`/`x` for player 2, `*` piece cells, answers `Y X`
+    Plateau,
+}
+
+impl Dialect {
+    // Dialect of the board header, the second line the engine sends
+    pub fn detect(header: &str) -> Option<Dialect> {
+        if header.starts_with("Anfield") {
+            Some(Dialect::Anfield)
+        } else if header.starts_with("Plateau") {
+            Some(Dialect::Plateau)
+        } else {
+            None
+        }
+    }
+
+    // First word of the lines announcing a board
+    pub fn board_keyword(self) -> &'static str {
+        match self {
+            Dialect::Anfield => "Anfield",
+            Dialect::Plateau => "Plateau",
+        }
+    }
+
+    // Size given by a board or piece header
+    pub fn size(self, header: &str) -> Size {
+        let parts: Vec<&str> = header.trim_end_matches(':').split_whitespace().collect();
+        let first: usize = parts[1].parse().expect("Invalid size in header");
+        let second: usize = parts[2].parse().expect("Invalid size in header");
+        match self {
+            Dialect::Anfield => Size { width: first, height: second },
+            Dialect::Plateau => Size { width: second, height: first },
+        }
+    }
+
+    pub fn board_cell(self, cell: char) -> char {
+        match (self, cell) {
+            (Dialect::Plateau, 'O') => '@',
+            (Dialect::Plateau, 'o') => 'a',
+            (Dialect::Plateau, 'X') => '$',
+            (Dialect::Plateau, 'x') => 's',
+            _ => cell,
+        }
+    }
+
+    pub fn piece_cell(self, cell: char) -> char {
+        match (self, cell) {
+            (Dialect::Plateau, '*') => 'O',
+            _ => cell,
+        }
+    }
+
+    // Answer line for the position of the top left cell of the piece
+    pub fn answer(self, x: i32, y: i32) -> String {
+        match self {
+            Dialect::Anfield => format!("{} {}", x, y),
+            Dialect::Plateau => format!("{} {}", y, x),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(Dialect::detect("Anfield 20 15:"), Some(Dialect::Anfield));
+        assert_eq!(Dialect::detect("Plateau 15 17:"), Some(Dialect::Plateau));
+        assert_eq!(Dialect::detect("Piece 2 2:"), None);
+    }
+
+    #[test]
+    fn test_plateau_is_normalized() {
+        let size = Dialect::Plateau.size("Plateau 15 17:");
+        assert_eq!((size.width, size.height), (17, 15));
+        let size = Dialect::Anfield.size("Anfield 20 15:");
+        assert_eq!((size.width, size.height), (20, 15));
+
+        let row: String = "..OoXx.".chars().map(|c| Dialect::Plateau.board_cell(c)).collect();
+        assert_eq!(row, "..@a$s.");
+        let row: String = ".*O".chars().map(|c| Dialect::Plateau.piece_cell(c)).collect();
+        assert_eq!(row, ".OO");
+        // The 01 edu dialect is the one used inside the robot
+        assert_eq!(Dialect::Anfield.board_cell('O'), 'O');
+
+        assert_eq!(Dialect::Anfield.answer(3, 5), "3 5");
+        assert_eq!(Dialect::Plateau.answer(3, 5), "5 3");
+    }
+}
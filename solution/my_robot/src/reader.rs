@@ -0,0 +1,42 @@
+use std::io::BufRead;
+
+use crate::protocol::Dialect;
+
+// Reads the game engine input a line at a time into the same buffer, so that reading a turn
+// allocates nothing once the buffer has grown to the longest line.
+pub struct Reader<R: BufRead> {
+    input: R,
+    line: String,
+    // Dialect the boards and pieces are read in, set once the board header is known
+    pub dialect: Dialect,
+}
+
+impl<R: BufRead> Reader<R> {
+    pub fn new(input: R) -> Self {
+        Reader { input, line: String::new(), dialect: Dialect::default() }
+    }
+
+    // Next line without its line ending, None at the end of the input or on a read error
+    pub fn next_line(&mut self) -> Option<&str> {
+        self.line.clear();
+        match self.input.read_line(&mut self.line) {
+            Ok(0) | Err(_) => None,
+            Ok(_) => Some(self.line.trim_end_matches(['\n', '\r'])),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_line() {
+        let mut reader = Reader::new("$$$ exec p1 : [robots/bender]\r\nAnfield 20 15:\n\nlast".as_bytes());
+        assert_eq!(reader.next_line(), Some("$$$ exec p1 : [robots/bender]"));
+        assert_eq!(reader.next_line(), Some("Anfield 20 15:"));
+        assert_eq!(reader.next_line(), Some(""));
+        assert_eq!(reader.next_line(), Some("last"));
+        assert_eq!(reader.next_line(), None);
+    }
+}
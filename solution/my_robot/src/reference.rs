@@ -0,0 +1,174 @@
+use std::fmt::Write;
+
+use crate::game::*;
+use crate::rng::Rng;
+
+// Placement rule written straight from the one of the engine, apart from the search of the
+// robot so that each checks the other. An answer is where the untrimmed piece goes: every block
+// must be on the board, on exactly one of our cells and on none of the enemy's, while the
+// padding of the piece may hang off the board.
+
+pub fn is_legal(field: &Field, piece: &Piece, (x, y): (i32, i32)) -> bool {
+    let mut overlaps = 0;
+    for pos in piece.cells.positions().filter(|pos| piece.cells[pos]) {
+        let (cell_y, cell_x) = (y + pos.y as i32, x + pos.x as i32);
+        if cell_y < 0 || cell_x < 0 {
+            return false;
+        }
+        match field.cells.get(&Pos { y: cell_y as usize, x: cell_x as usize }) {
+            None => return false,
+            Some(cell) if cell.is_enemy() => return false,
+            Some(cell) if cell.is_own() => overlaps += 1,
+            Some(_) => {}
+        }
+    }
+    overlaps == 1
+}
+
+// Answers as (x, y), row by row
+pub type Answers = Vec<(i32, i32)>;
+
+pub fn legal_answers(field: &Field, piece: &Piece) -> Answers {
+    let (height, width) = (field.height() as i32, field.width() as i32);
+    (1 - piece.height() as i32..height)
+        .flat_map(|y| (1 - piece.width() as i32..width).map(move |x| (x, y)))
+        .filter(|&answer| is_legal(field, piece, answer))
+        .collect()
+}
+
+// A board and a piece, drawn as in the fixtures of the tests
+#[derive(Debug, Clone)]
+struct Case {
+    board: Vec<String>,
+    piece: Vec<String>,
+    player_symbol: (char, char),
+}
+
+impl Case {
+    fn field(&self) -> Field {
+        Field::from_rows(&self.board.iter().map(String::as_str).collect::<Vec<_>>(), self.player_symbol)
+    }
+
+    fn piece(&self) -> Piece {
+        Piece::from_rows(&self.piece.iter().map(String::as_str).collect::<Vec<_>>())
+    }
+
+    // Answers of the robot and of the reference, when they differ
+    fn disagreement(&self) -> Option<(Answers, Answers)> {
+        let player = Player { _num: if self.player_symbol.0 == 'a' { 1 } else { 2 }, symbol: self.player_symbol, score: 0 };
+        let (field, piece) = (self.field(), self.piece());
+        let expected = legal_answers(&field, &piece);
+        let game = Game::new(player, field);
+        let found: Answers = game.valid_placements(&piece).iter().map(Placement::answer).collect();
+        (found != expected).then_some((found, expected))
+    }
+
+    // Cases one step smaller: a row or a column less, or a cell emptied, on the board or the piece
+    fn shrinks(&self) -> Vec<Case> {
+        let boards = smaller(&self.board).into_iter().map(|board| Case { board, ..self.clone() });
+        let pieces = smaller(&self.piece)
+            .into_iter()
+            // An engine piece has at least a block
+            .filter(|piece| piece.iter().any(|row| row.contains('O')))
+            .map(|piece| Case { piece, ..self.clone() });
+        boards.chain(pieces).collect()
+    }
+
+    // Smallest case found that still fails, shrinking greedily
+    fn shrink(mut self, fails: impl Fn(&Case) -> bool) -> Case {
+        while let Some(smaller) = self.shrinks().into_iter().find(&fails) {
+            self = smaller;
+        }
+        self
+    }
+}
+
+fn smaller(rows: &[String]) -> Vec<Vec<String>> {
+    let width = rows[0].chars().count();
+    let without = |row: &String, x: usize| -> String { row.chars().enumerate().filter(|&(col, _)| col != x).map(|(_, c)| c).collect() };
+    let mut candidates: Vec<Vec<String>> = Vec::new();
+    for y in (0..rows.len()).filter(|_| rows.len() > 1) {
+        candidates.push(rows.iter().enumerate().filter(|&(row, _)| row != y).map(|(_, row)| row.clone()).collect());
+    }
+    for x in (0..width).filter(|_| width > 1) {
+        candidates.push(rows.iter().map(|row| without(row, x)).collect());
+    }
+    for (y, row) in rows.iter().enumerate() {
+        for (x, _) in row.chars().enumerate().filter(|&(_, c)| c != '.') {
+            let mut emptied = rows.to_vec();
+            emptied[y] = row.chars().enumerate().map(|(col, c)| if col == x { '.' } else { c }).collect();
+            candidates.push(emptied);
+        }
+    }
+    candidates
+}
+
+// Random cases, the same on every run
+impl Rng {
+    fn rows(&mut self, height: usize, width: usize, cell: impl Fn(&mut Rng) -> char) -> Vec<String> {
+        (0..height).map(|_| (0..width).map(|_| cell(self)).collect()).collect()
+    }
+
+    fn case(&mut self) -> Case {
+        let (height, width) = (1 + self.below(10), 1 + self.below(10));
+        let board = self.rows(height, width, |rng| ['.', '.', '.', '.', 'a', '@', '@', 's', '$', '$'][rng.below(10)]);
+        let mut piece = Vec::new();
+        while !piece.iter().any(|row: &String| row.contains('O')) {
+            let (height, width) = (1 + self.below(5), 1 + self.below(5));
+            piece = self.rows(height, width, |rng| if rng.below(5) < 2 { 'O' } else { '.' });
+        }
+        let player_symbol = if self.below(2) == 0 { ('a', '@') } else { ('s', '$') };
+        Case { board, piece, player_symbol }
+    }
+}
+
+fn report(case: &Case) -> String {
+    let (found, expected) = case.disagreement().unwrap();
+    let mut report = format!("player {:?}\nboard:\n", case.player_symbol);
+    for row in &case.board {
+        let _ = writeln!(report, "  {}", row);
+    }
+    report.push_str("piece:\n");
+    for row in &case.piece {
+        let _ = writeln!(report, "  {}", row);
+    }
+    let _ = write!(report, "robot: {:?}\nreference: {:?}", found, expected);
+    report
+}
+
+#[test]
+fn test_search_agrees_with_the_reference() {
+    let mut rng = Rng::new(1);
+    let mut with_answers = 0;
+    for _ in 0..5000 {
+        let case = rng.case();
+        if case.disagreement().is_some() {
+            let case = case.shrink(|case| case.disagreement().is_some());
+            panic!("Placement search and reference disagree, shrunk to:\n{}", report(&case));
+        }
+        with_answers += usize::from(!legal_answers(&case.field(), &case.piece()).is_empty());
+    }
+    // Most boards should leave the piece somewhere to go, or the cases check little
+    assert!(with_answers > 1000, "Only {} cases with a legal answer", with_answers);
+}
+
+#[test]
+fn test_shrink_to_the_smallest_failing_case() {
+    let case = Case { board: vec!["..@".into(), "s..".into()], piece: vec!["..".into(), ".O".into()], player_symbol: ('a', '@') };
+    let has_answer = |case: &Case| !legal_answers(&case.field(), &case.piece()).is_empty();
+    let case = case.shrink(has_answer);
+    assert_eq!((case.board, case.piece), (vec!["@".to_string()], vec!["O".to_string()]));
+}
+
+#[test]
+fn test_is_legal_on_the_untrimmed_piece() {
+    let field = Field::from_rows(&["@..", "...", "..$"], ('a', '@'));
+    let piece = Piece::from_rows(&["..", ".O", ".O"]);
+    // The padding may hang off the board, the blocks may not
+    assert!(is_legal(&field, &piece, (-1, -1)));
+    assert!(!is_legal(&field, &piece, (-1, -2)));
+    assert!(!is_legal(&field, &piece, (0, 0)));
+    // On an enemy cell as well
+    let field = Field::from_rows(&["@..", "$..", "..."], ('a', '@'));
+    assert!(!is_legal(&field, &piece, (-1, -1)));
+}
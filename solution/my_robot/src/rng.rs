@@ -0,0 +1,38 @@
+// xorshift64*: small and the same on every platform, for random test cases and for the random
+// baseline bot to play the same game again from the same seed
+pub struct Rng(u64);
+
+impl Rng {
+    // A seed of 0 would only ever give 0, it is replaced
+    pub fn new(seed: u64) -> Self {
+        Rng(if seed == 0 { 0x9e37_79b9_7f4a_7c15 } else { seed })
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    // Number in 0..n, n > 0
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() >> 32) as usize % n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_numbers() {
+        let numbers = |seed| {
+            let mut rng = Rng::new(seed);
+            (0..8).map(|_| rng.below(10)).collect::<Vec<_>>()
+        };
+        assert_eq!(numbers(42), numbers(42));
+        assert_ne!(numbers(42), numbers(43));
+        assert!(numbers(0).iter().any(|&n| n != 0));
+    }
+}
@@ -0,0 +1,103 @@
+use std::env;
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::field::Field;
+use crate::game::Placement;
+use crate::player::Player;
+
+// Set to a file path to make the robot write its decision trace there
+pub const TRACE_ENV: &str = "FILLER_TRACE";
+
+// Machine readable trace of every decision, one JSON object per line.
+// The first line describes the game, every following line is one turn:
+// {"player":1,"width":20,"height":15}
+// {"turn":1,"answer":[7,1],"candidates":[{"x":7,"y":1,"score":40,"breakdown":[["cells",4],["enemy_distance",36]]}]}
+// Candidate positions are answer coordinates, so they can be negative.
+#[derive(Debug, Clone)]
+pub struct Trace {
+    pub path: PathBuf,
+}
+
+impl Trace {
+    // Creates (or truncates) the trace file if the env variable is set
+    pub fn from_env(player: &Player, field: &Field) -> Option<Self> {
+        let path = PathBuf::from(env::var_os(TRACE_ENV)?);
+        let mut file = File::create(&path).ok()?;
+        writeln!(
+            file,
+            "{{\"player\":{},\"width\":{},\"height\":{}}}",
+            player._num, field.width(), field.height()
+        )
+        .ok()?;
+        Some(Trace { path })
+    }
+
+    // Appends one turn to the trace. Failing to write must never stop the robot from answering.
+    pub fn write_turn(&self, turn: usize, placements: &[Placement], best: &Placement) {
+        let mut line = format!("{{\"turn\":{},\"answer\":{},\"candidates\":[", turn, answer_json(best));
+        for (i, placement) in placements.iter().enumerate() {
+            if i > 0 {
+                line.push(',');
+            }
+            let (x, y) = placement.answer();
+            let breakdown: Vec<String> = placement
+                .breakdown
+                .iter()
+                .map(|(source, value)| format!("[\"{}\",{}]", source, value))
+                .collect();
+            line.push_str(&format!(
+                "{{\"x\":{},\"y\":{},\"score\":{},\"breakdown\":[{}]}}",
+                x,
+                y,
+                placement.score,
+                breakdown.join(",")
+            ));
+        }
+        line.push_str("]}");
+
+        if let Ok(mut file) = OpenOptions::new().append(true).open(&self.path) {
+            let _ = writeln!(file, "{}", line);
+        }
+    }
+}
+
+fn answer_json(placement: &Placement) -> String {
+    let (x, y) = placement.answer();
+    format!("[{},{}]", x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::Pos;
+    use crate::piece::Piece;
+
+    #[test]
+    fn test_write_turn() {
+        let path = env::temp_dir().join(format!("my_robot_trace_{}.jsonl", std::process::id()));
+        File::create(&path).unwrap();
+        let trace = Trace { path: path.clone() };
+
+        let piece = Piece::from_rows(&[".OO", "..."]);
+        let mut placement = Placement {
+            pos: Pos { x: 2, y: 1 },
+            score: 0,
+            piece: &piece,
+            breakdown: Vec::new(),
+        };
+        placement.add_score("cells", 4);
+        placement.add_score("enclosing", 3);
+        placement.add_score("enclosing", 3);
+
+        trace.write_turn(5, &[placement.clone()], &placement);
+        let written = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(
+            written,
+            "{\"turn\":5,\"answer\":[1,1],\"candidates\":[{\"x\":1,\"y\":1,\"score\":10,\"breakdown\":[[\"cells\",4],[\"enclosing\",6]]}]}\n"
+        );
+    }
+}
@@ -2,93 +2,59 @@ use crate::piece::Piece;
 use crate::grid::Grid;
 use crate::field::Field;
 use crate::game::{Pos, Placement};
+use crate::board_analysis::BoardAnalysis;
+use crate::endgame::evaluate_placement_for_endgame;
+use crate::analysis::{choke_points, corridors};
+use crate::phase::Weights;
+use crate::parallel;
 
 use std::iter::Iterator;
 
-pub fn get_average_pos(field: &Field, player_symbol: (char, char), looking_for_enemy: bool) -> Pos {
-    let mut average_pos: Pos = Pos { y: 0, x: 0 };
-    let mut all_pos: Vec<Pos> = Vec::new();
-    let mut cell_count: usize = 0;
-    for y in 0..field.size.height {
-        for x in 0..field.size.width {
-            let cell = Some(field.cells[y][x]);
-            if looking_for_enemy && is_enemy_cell(cell, player_symbol) {
-                all_pos.push(Pos { y, x });
-                cell_count += 1;
-            } else if !looking_for_enemy && is_player_cell(cell, player_symbol) {
-                all_pos.push(Pos { y, x });
-                cell_count += 1;
-            }
+// Shapes used to count the placements left to the enemy: dominoes, an L and a square
+const MOBILITY_SHAPES: [&[&str]; 4] = [&["OO"], &["O", "O"], &["OO", "O."], &["OO", "OO"]];
+// Most cells a cut corridor counts for
+const CHOKE_POINT_MAX_SEALED: usize = 40;
+
+// Scores the placements in place with the weighted evaluators and returns a copy of the best one.
+// While the opening book has a target the race heads for it instead of the enemy.
+pub fn evaluate_placements<'p>(field: &Field, analysis: &BoardAnalysis, valid_placements: &mut [Placement<'p>], book_target: Option<Pos>, weights: &Weights, prev_pieces: &[Piece]) -> Placement<'p> {
+    if weights.enemy_distance > 0 {
+        match book_target {
+            Some(target) => evaluate_placement_for_opening_book(field, valid_placements, target, weights.enemy_distance),
+            None => evaluate_placement_for_enemy_distance(field, valid_placements, analysis.enemy_center(), weights.enemy_distance),
         }
     }
-    for pos in all_pos.iter() {
-        average_pos.x += pos.x;
-        average_pos.y += pos.y;
-    }
-    average_pos.x /= cell_count;
-    average_pos.y /= cell_count;
-    average_pos
-}
-
-
-pub fn get_adjacent_cells(field: &Field, placement: &Pos) -> (Option<char>, Option<char>, Option<char>, Option<char>) {
-    let mut prev_y_cell: Option<char> = None;
-    let mut next_y_cell: Option<char> = None;
-    let mut prev_x_cell: Option<char> = None;
-    let mut next_x_cell: Option<char> = None;
-
-    if placement.y > 0 {
-        prev_y_cell = Some(field.cells()[placement.y - 1][placement.x]);
-    }
-    if placement.y + 1 < field.height() {
-        next_y_cell = Some(field.cells()[placement.y + 1][placement.x]);
+    if weights.enemy_mobility > 0 {
+        evaluate_placement_for_enemy_mobility(field, analysis, valid_placements, weights.enemy_mobility);
     }
-    if placement.x > 0 {
-        prev_x_cell = Some(field.cells()[placement.y][placement.x - 1]);
+    if weights.choke_point > 0 {
+        evaluate_placement_for_choke_points(field, valid_placements, weights.choke_point);
     }
-    if placement.x + 1 < field.width() {
-        next_x_cell = Some(field.cells()[placement.y][placement.x + 1]);
+    // Once our region is sealed off the exact packing search replaces the perfect fit estimate
+    let in_endgame = weights.endgame && evaluate_placement_for_endgame(field, valid_placements, prev_pieces);
+    let found_enclosing_cells = weights.enclosing > 0
+        && evaluate_placement_for_enclosing_cells(field, analysis, valid_placements, weights.enclosing);
+    if !found_enclosing_cells && !in_endgame && weights.perfect_fit > 0 {
+        evaluate_placement_for_perfect_fit(field, valid_placements, weights.perfect_fit, prev_pieces);
     }
 
-    (prev_y_cell, next_y_cell, prev_x_cell, next_x_cell)
+    valid_placements.iter().max_by_key(|placement| placement.score).unwrap().clone()
 }
 
-pub fn evaluate_placements(field: &Field, mut valid_placements: Vec<Placement>, enemy_pos: Pos, current_turn: usize, player_symbol: (char, char), prev_pieces: &Vec<Piece>) -> Placement {
-    let has_touched_enemy_cell = check_if_touching_enemy_cell(&field, player_symbol);
-    if !has_touched_enemy_cell {
-        evaluate_placement_for_enemy_distance(&field, &mut valid_placements, enemy_pos, current_turn);
-    }
-    let found_enclosing_cells = evaluate_placement_for_enclosing_cells(&field, &mut valid_placements, player_symbol);
-    if !found_enclosing_cells {
-        evaluate_placement_for_perfect_fit(&field, &mut valid_placements, current_turn, prev_pieces, player_symbol);
-    }
-
-    valid_placements.into_iter().max_by_key(|placement| placement.score).unwrap()
+pub fn evaluate_placement_for_enemy_distance(field: &Field, placements: &mut [Placement], enemy_pos: Pos, score: i32) {
+    // Evaluating whether the placement is closing in on the enemy or not
+    score_closest_placements(field, placements, enemy_pos, score, "enemy_distance");
 }
 
-pub fn check_if_touching_enemy_cell(field: &Field, player_symbol: (char, char)) -> bool {
-    for y in 0..field.height() {
-        for x in 0..field.width() {
-            let cell = field.cells[y][x];
-            if is_enemy_cell(Some(cell), player_symbol) {
-                let adjacent_cells = get_adjacent_cells(field, &Pos { y, x });
-                if (adjacent_cells.0.is_some() && is_player_cell(adjacent_cells.0, player_symbol)) ||
-                (adjacent_cells.1.is_some() && is_player_cell(adjacent_cells.1, player_symbol)) ||
-                (adjacent_cells.2.is_some() && is_player_cell(adjacent_cells.2, player_symbol)) ||
-                (adjacent_cells.3.is_some() && is_player_cell(adjacent_cells.3, player_symbol)) {
-                    return true;
-                }
-            }
-        }
-    }
-    false
+pub fn evaluate_placement_for_opening_book(field: &Field, placements: &mut [Placement], target: Pos, score: i32) {
+    // Evaluating whether the placement is closing in on the next waypoint of the opening book
+    score_closest_placements(field, placements, target, score, "opening_book");
 }
 
-pub fn evaluate_placement_for_enemy_distance(field: &Field, placements: &mut Vec<Placement>, enemy_pos: Pos, current_turn: usize) {
-    // Evaluating whether the placement is closing in on the enemy or not
-    // Less important late-game (high current_turn)
+// The placement closest to the target gets the full score, the next ones half the score of the previous one
+fn score_closest_placements(field: &Field, placements: &mut [Placement], target: Pos, score: i32, source: &'static str) {
     let mut best_placements: Vec<(f32, usize)> = Vec::new();
-    let mut current_score_addition = ((36.0 * (2.0 as f32).powf(-0.15 * current_turn as f32)) as i32).max(4);
+    let mut current_score_addition = score;
     let mut score_list = Vec::new();
 
     while current_score_addition > 1 {
@@ -97,26 +63,23 @@ pub fn evaluate_placement_for_enemy_distance(field: &Field, placements: &mut Vec
     }
 
     for (i, placement) in placements.iter().enumerate() {
-        let center = get_center_of_piece(field, &placement.pos, &placement.piece);
-        let distance = (((center.x).abs_diff(enemy_pos.x).pow(2) + (center.y).abs_diff(enemy_pos.y).pow(2)) as f32).sqrt();
+        let center = get_center_of_piece(field, &placement.pos, placement.piece);
+        let distance = (((center.x).abs_diff(target.x).pow(2) + (center.y).abs_diff(target.y).pow(2)) as f32).sqrt();
         best_placements.push((distance, i));
     }
 
     best_placements.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
     best_placements.truncate(score_list.len());
 
-    let mut score_index = 0;
-    for (_, index) in best_placements {
+    for (score_index, (_, index)) in best_placements.into_iter().enumerate() {
         // println!("Distance calc adding {} score to placement at pos {:?}", score_list[score_index], placements[index].pos);
-        placements[index].score += score_list[score_index];
-        score_index += 1;
+        placements[index].add_score(source, score_list[score_index]);
     }
 }
 
-pub fn evaluate_placement_for_perfect_fit(field: &Field, placements: &mut Vec<Placement>, current_turn: usize, _prev_pieces: &Vec<Piece>, _player_symbol: (char, char)) {
+pub fn evaluate_placement_for_perfect_fit(field: &Field, placements: &mut [Placement], score: i32, _prev_pieces: &[Piece]) {
     // Evaluating whether the placement perfectly fills gaps in the field
-    // More important late-game (high current_turn)
-    let current_score_addition = ((1.07 as f32).powf(current_turn as f32) as i32).min(50);
+    let current_score_addition = score;
 
     for placement in placements {
         let mut is_perfect_fit = true;
@@ -128,8 +91,8 @@ pub fn evaluate_placement_for_perfect_fit(field: &Field, placements: &mut Vec<Pl
                     continue;
                 }
                 let field_cell = field.cells[y][x];
-                let piece_cell = placement.piece.cells()[y - top_left.y][x - top_left.x];
-                if field_cell == '.' && piece_cell == '.' {
+                let piece_cell = placement.piece.cells[y - top_left.y][x - top_left.x];
+                if field_cell.is_empty() && !piece_cell {
                     is_perfect_fit = false;
                     break 'piece_loop;
                 }
@@ -137,63 +100,158 @@ pub fn evaluate_placement_for_perfect_fit(field: &Field, placements: &mut Vec<Pl
         }
         if is_perfect_fit {
             // println!("Perfect fit adding {} score to placement at pos {:?}", current_score_addition, placement.pos);
-            placement.score += (current_score_addition as f32) as i32;
+            placement.add_score("perfect_fit", current_score_addition);
         }
     }
 }
 
-pub fn evaluate_placement_for_enclosing_cells(field: &Field, placements: &mut Vec<Placement>, player_symbol: (char, char)) -> bool {
-    // Evaluating whether any of the cells are enclosing other cells
-    let mut found_enclosing_cells = false;
-    let enclosing_score_addition = 12;
-
-    let enclosing_positions = get_enclosing_positions(field, player_symbol);
-
-    for placement in placements {
-        let top_left: Pos = placement.pos.clone();
-        let bottom_right: Pos = Pos { x: placement.pos.x + placement.piece.size.width, y: placement.pos.y + placement.piece.size.height };
-        for y in top_left.y..bottom_right.y {
-            for x in top_left.x..bottom_right.x {
-                let piece_cell = placement.piece.cells()[y - top_left.y][x - top_left.x];
-                if y >= field.height() || x >= field.width() || piece_cell != 'O' {
+pub fn evaluate_placement_for_enemy_mobility(field: &Field, analysis: &BoardAnalysis, placements: &mut [Placement], score: i32) {
+    // Evaluating how many placements the enemy loses next turn, for a few small shapes that
+    // stand for the pieces it may get
+    let width = field.width();
+
+    // Legal enemy placements covering each empty cell
+    let mut covering: Vec<Vec<usize>> = vec![Vec::new(); width * field.height()];
+    let mut enemy_placements = 0;
+    for shape in MOBILITY_SHAPES {
+        let cells: Grid<bool> = Grid::from_rows(shape.iter().map(|row| row.chars().map(|c| c == 'O').collect()).collect());
+        let (height, shape_width) = (cells.height(), cells.width());
+        if height > field.height() || shape_width > width {
+            continue;
+        }
+        for y in 0..=field.height() - height {
+            for x in 0..=width - shape_width {
+                // Every shape cell is next to its top left cell, which must then be next to an enemy cell
+                if analysis.enemy_distance(&Pos { y, x }) > 1
+                    || !is_valid_placement(field, &cells, &Pos { y, x }, true) {
                     continue;
                 }
-                let mut min_score_divider = 3;
-                for enclosing_position in &enclosing_positions {
-                    if enclosing_position.0.y == y && enclosing_position.0.x == x {
-                        if enclosing_position.1 < min_score_divider {
-                            min_score_divider = enclosing_position.1;
-                            if min_score_divider == 1 {
-                                found_enclosing_cells = true;
-                                break;
-                            }
+                for (dy, row) in cells.rows().enumerate() {
+                    for (dx, &block) in row.iter().enumerate() {
+                        if block && field.cells[y + dy][x + dx].is_empty() {
+                            covering[(y + dy) * width + x + dx].push(enemy_placements);
                         }
                     }
                 }
-                // println!("Enclosing cells adding {} score to placement at pos {:?}", enclosing_score_addition / min_score_divider as i32, placement.pos);
-                placement.score += enclosing_score_addition / min_score_divider as i32;
+                enemy_placements += 1;
             }
         }
     }
-    found_enclosing_cells
+
+    // An enemy placement is lost when the placement covers any of its cells
+    parallel::for_each_chunk(placements, |chunk| {
+        let mut lost_by = vec![usize::MAX; enemy_placements];
+        for (i, placement) in chunk.iter_mut().enumerate() {
+            let mut lost = 0;
+            for (dy, row) in placement.piece.trimmed_cells.rows().enumerate() {
+                for (dx, &block) in row.iter().enumerate() {
+                    if !block {
+                        continue;
+                    }
+                    for &enemy_placement in &covering[(placement.pos.y + dy) * width + placement.pos.x + dx] {
+                        if lost_by[enemy_placement] != i {
+                            lost_by[enemy_placement] = i;
+                            lost += 1;
+                        }
+                    }
+                }
+            }
+            if lost > 0 {
+                // println!("Enemy mobility adding {} score to placement at pos {:?}", lost * score, placement.pos);
+                placement.add_score("enemy_mobility", lost * score);
+            }
+        }
+    });
 }
 
-pub fn is_enemy_cell(cell: Option<char>, player_symbol: (char, char)) -> bool {
-    if cell.is_none()
-        || cell.unwrap() == '.'
-        || cell.unwrap() == player_symbol.0
-        || cell.unwrap() == player_symbol.1
-    {
-        return false;
+pub fn evaluate_placement_for_choke_points(field: &Field, placements: &mut [Placement], score: i32) {
+    // Evaluating whether the placement seals empty cells off from the enemy. Each corridor the
+    // placement cuts counts once, for its covered choke point sealing the most cells.
+    let corridors = corridors(&choke_points(field));
+    if corridors.is_empty() {
+        return;
     }
-    true
+    // Corridor and sealed cells of each choke point
+    let mut choke_point_at = Grid::new(field.size().clone(), None);
+    for (id, corridor) in corridors.iter().enumerate() {
+        for choke_point in corridor {
+            choke_point_at[choke_point.pos.y][choke_point.pos.x] = Some((id, choke_point.sealed.min(CHOKE_POINT_MAX_SEALED)));
+        }
+    }
+
+    parallel::for_each_chunk(placements, |chunk| {
+        let mut sealed_by_corridor = vec![0; corridors.len()];
+        for placement in chunk {
+            sealed_by_corridor.iter_mut().for_each(|sealed| *sealed = 0);
+            for (dy, row) in placement.piece.trimmed_cells.rows().enumerate() {
+                for (dx, &block) in row.iter().enumerate() {
+                    if let (true, Some((id, sealed))) = (block, choke_point_at[placement.pos.y + dy][placement.pos.x + dx]) {
+                        sealed_by_corridor[id] = sealed_by_corridor[id].max(sealed);
+                    }
+                }
+            }
+            let sealed: usize = sealed_by_corridor.iter().sum();
+            if sealed > 0 {
+                // println!("Choke points adding {} score to placement at pos {:?}", sealed as i32 * score, placement.pos);
+                placement.add_score("choke_point", sealed as i32 * score);
+            }
+        }
+    });
+}
+
+pub fn evaluate_placement_for_enclosing_cells(field: &Field, analysis: &BoardAnalysis, placements: &mut [Placement], score: i32) -> bool {
+    // Evaluating whether any of the cells are enclosing other cells: the score is divided by the
+    // number of straight steps to an enemy cell, 3 at most
+    let enclosing_score_addition = score;
+
+    let found_by_chunk = parallel::for_each_chunk(placements, |chunk| {
+        let mut found_enclosing_cells = false;
+        for placement in chunk {
+            let top_left: Pos = placement.pos.clone();
+            let bottom_right: Pos = Pos { x: placement.pos.x + placement.piece.size.width, y: placement.pos.y + placement.piece.size.height };
+            for y in top_left.y..bottom_right.y {
+                for x in top_left.x..bottom_right.x {
+                    let piece_cell = placement.piece.cells[y - top_left.y][x - top_left.x];
+                    if y >= field.height() || x >= field.width() || !piece_cell {
+                        continue;
+                    }
+                    let min_score_divider = analysis.enclosing_divider(&Pos { y, x }).unwrap_or(3);
+                    if min_score_divider == 1 {
+                        found_enclosing_cells = true;
+                    }
+                    // println!("Enclosing cells adding {} score to placement at pos {:?}", enclosing_score_addition / min_score_divider as i32, placement.pos);
+                    placement.add_score("enclosing", enclosing_score_addition / min_score_divider as i32);
+                }
+            }
+        }
+        found_enclosing_cells
+    });
+    found_by_chunk.contains(&true)
 }
 
-pub fn is_player_cell(cell: Option<char>, player_symbol: (char, char)) -> bool {
-    if cell.is_some() && (cell.unwrap() == player_symbol.0 || cell.unwrap() == player_symbol.1) {
-        return true;
+// A piece must cover exactly one cell of the player and no cell of the other player, the
+// player being the enemy when `as_enemy` is set.
+// `pos` is where the top left cell of `cells` goes, the piece must be inside the field.
+pub fn is_valid_placement(field: &Field, cells: &Grid<bool>, pos: &Pos, as_enemy: bool) -> bool {
+    let mut overlap = 0;
+    for (dy, row) in cells.rows().enumerate() {
+        for (dx, &block) in row.iter().enumerate() {
+            if !block {
+                continue;
+            }
+            let cell = field.cells[pos.y + dy][pos.x + dx];
+            let cell = if as_enemy { cell.flipped() } else { cell };
+            if cell.is_own() {
+                overlap += 1;
+                if overlap > 1 {
+                    return false;
+                }
+            } else if cell.is_enemy() {
+                return false;
+            }
+        }
     }
-    false
+    overlap == 1
 }
 
 pub fn get_center_of_piece(field: &Field, placement: &Pos, piece: &Piece) -> Pos {
@@ -208,130 +266,44 @@ pub fn get_center_of_piece(field: &Field, placement: &Pos, piece: &Piece) -> Pos
     Pos { y, x }
 }
 
-pub fn get_enclosing_positions(field: &Field, player_symbol: (char, char)) -> Vec<(Pos, usize)> {
-    // Finding all positions that would enclose an enemy cell, as well as a score divider (starting from 1, going up to 3) (how many cells are between the current cell and the enemy cell)
-    let mut enclosing_positions: Vec<(Pos, usize)> = Vec::new();
-    for y in 0..field.height() {
-        for x in 0..field.width() {
-            let cell = field.cells[y][x];
-            if is_enemy_cell(Some(cell), player_symbol) {
-                for steps_away_index in 1..=3 {
-                    if y as i32 - steps_away_index as i32 >= 0 {
-                        let above_cell = field.cells[y - steps_away_index][x];
-                        if above_cell == '.' {
-                            enclosing_positions.push((Pos { y: y - steps_away_index, x }, steps_away_index));
-                        }
-                    }
-                    if y + steps_away_index < field.height() {
-                        let below_cell = field.cells[y + steps_away_index][x];
-                        if below_cell == '.' {
-                            enclosing_positions.push((Pos { y: y + steps_away_index, x }, steps_away_index));
-                        }
-                    }
-                    if x as i32 - steps_away_index as i32 >= 0 {
-                        let left_cell = field.cells[y][x - steps_away_index];
-                        if left_cell == '.' {
-                            enclosing_positions.push((Pos { y, x: x - steps_away_index }, steps_away_index));
-                        }
-                    }
-                    if x + steps_away_index < field.width() {
-                        let right_cell = field.cells[y][x + steps_away_index];
-                        if right_cell == '.' {
-                            enclosing_positions.push((Pos { y, x: x + steps_away_index }, steps_away_index));
-                        }
-                    }
-                }
-            }
-        }
-    }
-    enclosing_positions
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::grid::Size;
 
     fn create_test_field() -> Field {
-        Field {
-            size: Size {
-                width: 4,
-                height: 4,
-            },
-            cells: vec![
-                vec!['.', 'a', '.', '.'],
-                vec!['.', '.', '.', '.'],
-                vec!['.', '.', 's', '.'],
-                vec!['.', '.', '.', '.'],
-            ],
-        }
+        Field::from_rows(&[
+            ".a..",
+            "....",
+            "..s.",
+            "....",
+        ], ('a', '@'))
     }
 
     fn create_test_piece() -> Piece {
-        Piece {
-            size: Size {
-                width: 2,
-                height: 2,
-            },
-            cells: vec![vec!['O', '.'], vec!['.', 'O']],
-            trimmed_size: Size {
-                width: 2,
-                height: 2,
-            },
-            trimmed_cells: vec![vec!['O', '.'], vec!['.', 'O']],
-            symbol_count: 2,
-            offset: (0, 0),
-        }
+        Piece::from_rows(&["O.", ".O"])
     }
 
-    fn create_test_placement(pos: Pos, score: i32) -> Placement {
+    fn create_test_placement(piece: &Piece, pos: Pos, score: i32) -> Placement<'_> {
         Placement {
             pos,
-            piece: create_test_piece(),
+            piece,
             score,
+            breakdown: Vec::new(),
         }
     }
 
-    #[test]
-    fn test_check_if_touching_enemy_cell() {
-        // Field with player 'a' at (0,1) and enemy 's' at (2,2)
-        let field = create_test_field();
-        let player_symbol = ('a', '@');
-
-        // They are not adjacent, so should return false
-        let result = check_if_touching_enemy_cell(&field, player_symbol);
-        assert_eq!(result, false);
-
-        // Create a field where they are adjacent
-        let field_adjacent = Field {
-            size: Size {
-                width: 4,
-                height: 4,
-            },
-            cells: vec![
-                vec!['.', 'a', 's', '.'], // Player and enemy adjacent
-                vec!['.', '.', '.', '.'],
-                vec!['.', '.', '.', '.'],
-                vec!['.', '.', '.', '.'],
-            ],
-        };
-
-        let result_adjacent = check_if_touching_enemy_cell(&field_adjacent, player_symbol);
-        assert_eq!(result_adjacent, true);
-    }
-
     #[test]
     fn test_evaluate_placement_for_enemy_distance() {
         let field = create_test_field();
         let enemy_pos = Pos { x: 2, y: 2 }; // Enemy at (2,2)
-        let current_turn = 1;
+        let piece = create_test_piece();
 
         let mut placements = vec![
-            create_test_placement(Pos { x: 0, y: 0 }, 0), // Far from enemy
-            create_test_placement(Pos { x: 1, y: 1 }, 0), // Close to enemy
+            create_test_placement(&piece, Pos { x: 0, y: 0 }, 0), // Far from enemy
+            create_test_placement(&piece, Pos { x: 1, y: 1 }, 0), // Close to enemy
         ];
 
-        evaluate_placement_for_enemy_distance(&field, &mut placements, enemy_pos, current_turn);
+        evaluate_placement_for_enemy_distance(&field, &mut placements, enemy_pos, 36);
 
         // The closer placement should have higher score
         assert!(placements[1].score > placements[0].score);
@@ -341,32 +313,37 @@ mod tests {
     }
 
     #[test]
-    fn test_is_enemy_cell() {
-        let player_symbol = ('a', '@');
-
-        // Test enemy cells
-        assert_eq!(is_enemy_cell(Some('s'), player_symbol), true);
-        assert_eq!(is_enemy_cell(Some('$'), player_symbol), true);
-
-        // Test non-enemy cells
-        assert_eq!(is_enemy_cell(Some('a'), player_symbol), false); // Player cell
-        assert_eq!(is_enemy_cell(Some('@'), player_symbol), false); // Player cell
-        assert_eq!(is_enemy_cell(Some('.'), player_symbol), false); // Empty cell
-        assert_eq!(is_enemy_cell(None, player_symbol), false); // No cell
+    fn test_evaluate_placement_for_enemy_mobility() {
+        let field = Field::from_rows(&[
+            "a.....",
+            "......",
+            ".....s",
+        ], ('a', '@'));
+        let piece = create_test_piece();
+
+        let mut placements = vec![
+            create_test_placement(&piece, Pos { x: 0, y: 0 }, 0), // Out of the enemy reach
+            create_test_placement(&piece, Pos { x: 3, y: 0 }, 0), // Covers (4,1), where the enemy square fits
+        ];
+
+        let analysis = BoardAnalysis::new(&field);
+        evaluate_placement_for_enemy_mobility(&field, &analysis, &mut placements, 3);
+
+        assert_eq!(placements[0].score, 0);
+        assert_eq!(placements[1].breakdown, vec![("enemy_mobility", 3)]);
     }
 
     #[test]
-    fn test_is_player_cell() {
-        let player_symbol = ('a', '@');
-
-        // Test player cells
-        assert_eq!(is_player_cell(Some('a'), player_symbol), true);
-        assert_eq!(is_player_cell(Some('@'), player_symbol), true);
-
-        // Test non-player cells
-        assert_eq!(is_player_cell(Some('s'), player_symbol), false); // Enemy cell
-        assert_eq!(is_player_cell(Some('$'), player_symbol), false); // Enemy cell
-        assert_eq!(is_player_cell(Some('.'), player_symbol), false); // Empty cell
-        assert_eq!(is_player_cell(None, player_symbol), false); // No cell
+    fn test_is_valid_placement() {
+        let field = create_test_field();
+        let piece = create_test_piece();
+
+        // Exactly one cell on ours
+        assert!(is_valid_placement(&field, &piece.trimmed_cells, &Pos { x: 1, y: 0 }, false));
+        // No cell on ours
+        assert!(!is_valid_placement(&field, &piece.trimmed_cells, &Pos { x: 2, y: 0 }, false));
+        // Covers the enemy cell, which is the one cell on ours for the enemy
+        assert!(!is_valid_placement(&field, &piece.trimmed_cells, &Pos { x: 1, y: 1 }, false));
+        assert!(is_valid_placement(&field, &piece.trimmed_cells, &Pos { x: 1, y: 1 }, true));
     }
 }